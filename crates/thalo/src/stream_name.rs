@@ -72,6 +72,20 @@ pub use self::category::Category;
 pub use self::id::ID;
 
 /// A stream name containing a category, and optionally an ID.
+///
+/// The category and ID are separated by the first `-` character, so IDs may
+/// themselves contain `-` characters.
+///
+/// # Example
+///
+/// ```
+/// # use thalo::stream_name::StreamName;
+/// #
+/// let stream_name: StreamName = "counter-abc-123".parse().unwrap();
+/// assert_eq!(stream_name.category(), "counter");
+/// assert_eq!(stream_name.id().unwrap(), "abc-123");
+/// assert_eq!(stream_name.to_string(), "counter-abc-123");
+/// ```
 #[derive(Clone, Debug, Default, Hash, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct StreamName<'a>(Cow<'a, str>);
 
@@ -79,7 +93,7 @@ impl<'a> StreamName<'a> {
     /// ID separator.
     ///
     /// When a stream name contains an ID, it is separated by a
-    /// colon (`-`) character.
+    /// dash (`-`) character.
     ///
     /// Only the first `-` is the separator, and all other `-` characters in an
     /// ID are valid.
@@ -89,17 +103,42 @@ impl<'a> StreamName<'a> {
     /// `category-id`
     pub const ID_SEPARATOR: char = '-';
 
+    /// Creates a new stream name from a string.
+    ///
+    /// Returns an error if the stream name or its category is empty.
     pub fn new(stream_name: impl Into<Cow<'a, str>>) -> Result<Self, EmptyStreamName> {
         let stream_name = stream_name.into();
+        if stream_name.is_empty() || stream_name.starts_with(Self::ID_SEPARATOR) {
+            return Err(EmptyStreamName);
+        }
 
-        // TODO: Valstream_nameate
         Ok(StreamName(stream_name))
     }
 
+    /// Creates a stream name from a category and optional ID.
+    ///
+    /// Returns an error if the category is empty or contains a `-`, as the
+    /// stream name's category would then differ from `category`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use thalo::stream_name::{Category, StreamName, ID};
+    /// #
+    /// let category = Category::new("counter").unwrap();
+    /// let id = ID::new("abc-123").unwrap();
+    /// let stream_name = StreamName::from_parts(category, Some(&id)).unwrap();
+    /// assert_eq!(stream_name, "counter-abc-123");
+    /// ```
     pub fn from_parts(
         category: Category<'_>,
         id: Option<&ID<'_>>,
     ) -> Result<Self, EmptyStreamName> {
+        // Categories may be deserialized without validation.
+        if !Category::is_valid_format(&category) {
+            return Err(EmptyStreamName);
+        }
+
         let mut s = category.into_string();
         if let Some(id) = id {
             s.push(Self::ID_SEPARATOR);
@@ -109,23 +148,66 @@ impl<'a> StreamName<'a> {
         Ok(StreamName(Cow::Owned(s)))
     }
 
+    /// Returns the category, which is everything before the first `-`.
+    ///
+    /// If the stream name has no ID, the whole stream name is the category.
     pub fn category(&self) -> Category<'_> {
         self.split_once(Self::ID_SEPARATOR)
             .map(|(category, _)| Category(Cow::Borrowed(category)))
             .unwrap_or(Category(Cow::Borrowed(self.as_ref())))
     }
 
+    /// Returns the ID, which is everything after the first `-`.
+    ///
+    /// Returns `None` if the stream name has no ID.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use thalo::stream_name::StreamName;
+    /// #
+    /// let stream_name = StreamName::new("counter-abc").unwrap();
+    /// assert_eq!(stream_name.id().unwrap(), "abc");
+    ///
+    /// let stream_name = StreamName::new("counter-abc+def").unwrap();
+    /// let id = stream_name.id().unwrap();
+    /// assert_eq!(id, "abc+def");
+    /// assert_eq!(id.cardinal_id(), "abc");
+    ///
+    /// assert!(StreamName::new("counter").unwrap().id().is_none());
+    /// assert!(StreamName::new("counter-").unwrap().id().is_none());
+    /// ```
     pub fn id(&self) -> Option<ID<'_>> {
         self.split_once(Self::ID_SEPARATOR)
+            .filter(|(_, id)| !id.is_empty())
             .map(|(_, id)| ID(Cow::Borrowed(id)))
     }
 
-    /// Returns whether a `stream_name` is a category.
+    /// Returns whether a `stream_name` is a category, meaning it contains no ID.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use thalo::stream_name::StreamName;
+    /// #
+    /// assert!(StreamName::is_category("counter"));
+    /// assert!(!StreamName::is_category("counter-123"));
+    /// ```
     pub fn is_category(stream_name: &str) -> bool {
-        stream_name.contains(Self::ID_SEPARATOR)
+        !stream_name.contains(Self::ID_SEPARATOR)
+    }
+}
+
+impl str::FromStr for StreamName<'static> {
+    type Err = EmptyStreamName;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        StreamName::new(s.to_string())
     }
 }
 
+/// A stream name, category or ID is empty, or a category contains the `-` ID
+/// separator.
 #[derive(Clone, Copy, Debug, Error)]
 #[error("empty stream name")]
 pub struct EmptyStreamName;
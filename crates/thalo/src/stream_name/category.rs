@@ -4,7 +4,7 @@ use std::{fmt, str};
 use heck::ToLowerCamelCase;
 use serde::{Deserialize, Serialize};
 
-use super::EmptyStreamName;
+use super::{EmptyStreamName, StreamName};
 
 /// A stream category containing an entity name, and optionally category types.
 ///
@@ -46,15 +46,26 @@ impl<'a> Category<'a> {
     pub const COMPOUNT_TYPE_SEPARATOR: char = '+';
 
     /// Create a new Category from a string slice, validating its format.
+    ///
+    /// Returns an error if the category is empty, or contains the `-` ID
+    /// separator, as the category of a stream name ends at its first `-`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use thalo::stream_name::Category;
+    /// #
+    /// assert!(Category::new("account:command").is_ok());
+    /// assert!(Category::new("").is_err());
+    /// assert!(Category::new("bank-account").is_err());
+    /// ```
     pub fn new(category: impl Into<Cow<'a, str>>) -> Result<Self, EmptyStreamName> {
         let category = category.into();
+        if !Self::is_valid_format(&category) {
+            return Err(EmptyStreamName);
+        }
 
-        // TODO: Validate
-        // if Self::is_valid_format(&id_cow) {
         Ok(Category(category))
-        // } else {
-        //     Err("Invalid category format")
-        // }
     }
 
     pub fn from_parts(
@@ -62,7 +73,7 @@ impl<'a> Category<'a> {
         types: &[&str],
     ) -> Result<Self, EmptyStreamName> {
         let mut s = entity_name.into();
-        if s.is_empty() {
+        if !Self::is_valid_format(&s) {
             return Err(EmptyStreamName);
         }
 
@@ -76,6 +87,10 @@ impl<'a> Category<'a> {
             }
         }
 
+        if !Self::is_valid_format(&s) {
+            return Err(EmptyStreamName);
+        }
+
         Ok(Category(Cow::Owned(s)))
     }
 
@@ -88,7 +103,7 @@ impl<'a> Category<'a> {
     /// # Example
     ///
     /// ```
-    /// # use message_db::stream_name::Category;
+    /// # use thalo::stream_name::Category;
     /// #
     /// let category = Category::normalize("Bank_Account");
     /// assert_eq!(category, "bankAccount");
@@ -97,6 +112,12 @@ impl<'a> Category<'a> {
         category.to_lower_camel_case()
     }
 
+    /// Returns whether a category is non-empty and contains no `-` ID
+    /// separator.
+    pub(crate) fn is_valid_format(category: &str) -> bool {
+        !category.is_empty() && !category.contains(StreamName::ID_SEPARATOR)
+    }
+
     /// Category entity name.
     pub fn entity_name(&self) -> &str {
         self.split_once(Self::CATEGORY_TYPE_SEPARATOR)
@@ -32,49 +32,67 @@ impl<'a> ID<'a> {
 
     /// Creates a new ID from a string.
     ///
-    /// The provided `id` string is split by `+` characters if present to create
-    /// a compound ID.
+    /// If the `id` string contains `+` characters, it is a compound ID.
     ///
     /// Returns an error if the ID is empty.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use thalo::stream_name::ID;
+    /// #
+    /// assert!(ID::new("abc").is_ok());
+    /// assert!(ID::new("").is_err());
+    /// ```
     pub fn new(id: impl Into<Cow<'a, str>>) -> Result<Self, EmptyStreamName> {
         let id = id.into();
-        Ok(ID(id))
-        // if id.contains(Self::COMPOUND_ID_SEPARATOR) {
-        //     let ids: Vec<_> = id
-        //         .split(Self::COMPOUND_ID_SEPARATOR)
-        //         .map(|id| Cow::Borrowed(id))
-        //         .collect();
-
-        //     Self::new_compound(ids)
-        // } else {
-        //     if id.is_empty() {
-        //         return Err(EmptyStreamName);
-        //     }
+        if id.is_empty() {
+            return Err(EmptyStreamName);
+        }
 
-        //     Ok(ID(vec![Cow::Borrowed(id)]))
-        // }
+        Ok(ID(id))
     }
 
-    // Returns the cardinal ID.
-    //
-    // This is the first ID. If there is only one ID present, that is the
-    // cardinal ID.
-    //
-    // # Example
-    //
-    // ```
-    // # use message_db::stream_name::ID;
-    // #
-    // # fn main() -> message_db::Result<()> {
-    // let id = ID::new("account1+account2")?;
-    // assert_eq!(id.cardinal_id(), "account1");
-    // # Ok(())
-    // # }
+    /// Returns the cardinal ID.
+    ///
+    /// This is the first ID. If there is only one ID present, that is the
+    /// cardinal ID.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use thalo::stream_name::ID;
+    /// #
+    /// let id = ID::new("abc+def").unwrap();
+    /// assert_eq!(id.cardinal_id(), "abc");
+    ///
+    /// let id = ID::new("abc").unwrap();
+    /// assert_eq!(id.cardinal_id(), "abc");
+    /// ```
     pub fn cardinal_id(&self) -> &str {
         self.split_once(Self::COMPOUND_ID_SEPARATOR)
             .map(|(id, _)| id)
             .unwrap_or(&self.0)
     }
+
+    /// Returns an iterator over each ID in a compound ID.
+    ///
+    /// If there is only one ID present, it is the only item returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use thalo::stream_name::ID;
+    /// #
+    /// let id = ID::new("abc+def").unwrap();
+    /// assert_eq!(id.ids().collect::<Vec<_>>(), ["abc", "def"]);
+    ///
+    /// let id = ID::new("abc").unwrap();
+    /// assert_eq!(id.ids().collect::<Vec<_>>(), ["abc"]);
+    /// ```
+    pub fn ids(&self) -> impl Iterator<Item = &str> {
+        self.0.split(Self::COMPOUND_ID_SEPARATOR)
+    }
 }
 
 impl_eq! { ID<'a>, &'b str }
@@ -8,6 +8,7 @@ use syn::ItemEnum;
 
 pub struct DeriveCommand {
     ident: syn::Ident,
+    generics: syn::Generics,
     command_type: CommandType,
 }
 
@@ -55,6 +56,7 @@ impl Parse for DeriveCommand {
 
         Ok(DeriveCommand {
             ident: item_enum.ident,
+            generics: item_enum.generics,
             command_type,
         })
     }
@@ -74,8 +76,13 @@ impl DeriveCommand {
     fn expand_handle_impl(&self) -> TokenStream {
         let Self {
             ident,
+            generics,
             command_type,
         } = self;
+        let state_generics = crate::state_generics(generics);
+        let (impl_generics, _, _) = state_generics.split_for_impl();
+        let (_, ty_generics, where_clause) = generics.split_for_impl();
+        let predicates = where_clause.map(|where_clause| &where_clause.predicates);
 
         match command_type {
             CommandType::Unnamed(commands) => {
@@ -83,7 +90,7 @@ impl DeriveCommand {
                 let arms = commands.iter().map(|(name, path)| {
                     quote! {
                         #ident::#name(cmd) => {
-                            <T as ::thalo::Handle<#path>>::handle(&self.0, cmd)
+                            <__ThaloAggregate as ::thalo::Handle<#path>>::handle(&self.0, cmd)
                                 .map_err(|err|
                                     ::thalo::__macro_helpers::serde_json::to_value(err)
                                         .unwrap_or_else(|err|
@@ -96,15 +103,16 @@ impl DeriveCommand {
 
                 quote! {
                     #[automatically_derived]
-                    impl<T> ::thalo::Handle<#ident> for ::thalo::State<T>
+                    impl #impl_generics ::thalo::Handle<#ident #ty_generics> for ::thalo::State<__ThaloAggregate>
                     where
-                        T: ::thalo::Aggregate,
-                        #( T: ::thalo::Handle<#paths>, )*
-                        #( <T as ::thalo::Handle<#paths>>::Error: ::serde::Serialize, )*
+                        __ThaloAggregate: ::thalo::Aggregate,
+                        #( __ThaloAggregate: ::thalo::Handle<#paths>, )*
+                        #( <__ThaloAggregate as ::thalo::Handle<#paths>>::Error: ::serde::Serialize, )*
+                        #predicates
                     {
                         type Error = ::thalo::__macro_helpers::serde_json::Value;
 
-                        fn handle(&self, event: #ident) -> ::std::result::Result<::std::vec::Vec<<T as ::thalo::Aggregate>::Event>, Self::Error> {
+                        fn handle(&self, event: #ident #ty_generics) -> ::std::result::Result<::std::vec::Vec<<__ThaloAggregate as ::thalo::Aggregate>::Event>, Self::Error> {
                             match event {
                                 #( #arms, )*
                             }
@@ -113,14 +121,15 @@ impl DeriveCommand {
                 }
             }
             CommandType::Other => quote! {
-                impl<T> ::thalo::Handle<#ident> for ::thalo::State<T>
+                impl #impl_generics ::thalo::Handle<#ident #ty_generics> for ::thalo::State<__ThaloAggregate>
                 where
-                    T: ::thalo::Aggregate + ::thalo::Handle<#ident>,
+                    __ThaloAggregate: ::thalo::Aggregate + ::thalo::Handle<#ident #ty_generics>,
+                    #predicates
                 {
-                    type Error = <T as ::thalo::Handle<#ident>>::Error;
+                    type Error = <__ThaloAggregate as ::thalo::Handle<#ident #ty_generics>>::Error;
 
-                    fn handle(&self, cmd: #ident) -> ::std::result::Result<::std::vec::Vec<<Self as ::thalo::Aggregate>::Event>, Self::Error> {
-                        <T as ::thalo::Handle<#ident>>::handle(&self.0, cmd)
+                    fn handle(&self, cmd: #ident #ty_generics) -> ::std::result::Result<::std::vec::Vec<<Self as ::thalo::Aggregate>::Event>, Self::Error> {
+                        <__ThaloAggregate as ::thalo::Handle<#ident #ty_generics>>::handle(&self.0, cmd)
                     }
                 }
             },
@@ -130,15 +139,17 @@ impl DeriveCommand {
     fn expand_from_impls(&self) -> TokenStream {
         let Self {
             ident,
+            generics,
             command_type,
         } = self;
+        let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
 
         match command_type {
             CommandType::Unnamed(commands) => {
                 let from_impls = commands.iter().map(|(name, path)| {
                     quote! {
                         #[automatically_derived]
-                        impl ::std::convert::From<#path> for #ident {
+                        impl #impl_generics ::std::convert::From<#path> for #ident #ty_generics #where_clause {
                             fn from(cmd: #path) -> Self {
                                 #ident::#name(cmd)
                             }
@@ -8,6 +8,7 @@ use syn::ItemEnum;
 
 pub struct DeriveEvent {
     ident: syn::Ident,
+    generics: syn::Generics,
     events: HashMap<syn::Ident, syn::Path>,
 }
 
@@ -53,6 +54,7 @@ impl Parse for DeriveEvent {
 
         Ok(DeriveEvent {
             ident: item_enum.ident,
+            generics: item_enum.generics,
             events,
         })
     }
@@ -70,23 +72,32 @@ impl DeriveEvent {
     }
 
     fn expand_apply_impl(&self) -> TokenStream {
-        let Self { ident, events, .. } = self;
+        let Self {
+            ident,
+            generics,
+            events,
+        } = self;
+        let state_generics = crate::state_generics(generics);
+        let (impl_generics, _, _) = state_generics.split_for_impl();
+        let (_, ty_generics, where_clause) = generics.split_for_impl();
+        let predicates = where_clause.map(|where_clause| &where_clause.predicates);
 
         let paths = events.values();
         let arms = events.iter().map(|(name, path)| {
             quote! {
-                #ident::#name(event) => <T as ::thalo::Apply<#path>>::apply(&mut self.0, event)
+                #ident::#name(event) => <__ThaloAggregate as ::thalo::Apply<#path>>::apply(&mut self.0, event)
             }
         });
 
         quote! {
             #[automatically_derived]
-            impl<T> ::thalo::Apply<#ident> for ::thalo::State<T>
+            impl #impl_generics ::thalo::Apply<#ident #ty_generics> for ::thalo::State<__ThaloAggregate>
             where
-                T: ::thalo::Aggregate,
-                #( T: ::thalo::Apply<#paths>, )*
+                __ThaloAggregate: ::thalo::Aggregate,
+                #( __ThaloAggregate: ::thalo::Apply<#paths>, )*
+                #predicates
             {
-                fn apply(&mut self, event: #ident) {
+                fn apply(&mut self, event: #ident #ty_generics) {
                     match event {
                         #( #arms, )*
                     }
@@ -96,12 +107,17 @@ impl DeriveEvent {
     }
 
     fn expand_from_impls(&self) -> TokenStream {
-        let Self { ident, events, .. } = self;
+        let Self {
+            ident,
+            generics,
+            events,
+        } = self;
+        let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
 
         let from_impls = events.iter().map(|(name, path)| {
             quote! {
                 #[automatically_derived]
-                impl ::std::convert::From<#path> for #ident {
+                impl #impl_generics ::std::convert::From<#path> for #ident #ty_generics #where_clause {
                     fn from(event: #path) -> Self {
                         #ident::#name(event)
                     }
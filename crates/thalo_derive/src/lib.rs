@@ -4,6 +4,17 @@ use event::DeriveEvent;
 mod command;
 mod event;
 
+/// Returns the generics for implementing a trait on `thalo::State`, which are
+/// the enum's generics followed by the aggregate type parameter.
+///
+/// The aggregate parameter is named `__ThaloAggregate` so it can't collide
+/// with the enum's own parameters.
+fn state_generics(generics: &syn::Generics) -> syn::Generics {
+    let mut generics = generics.clone();
+    generics.params.push(syn::parse_quote!(__ThaloAggregate));
+    generics
+}
+
 /// Used to implement traits for an aggregate command enum.
 ///
 /// A command enum can either wrap nested command structs which each implement
@@ -16,6 +27,104 @@ mod event;
 ///
 /// If the command uses nested command structs, then a `From` implementation
 /// will be generated for each variant.
+///
+/// # Per-Command Handlers
+///
+/// When every variant wraps a command struct, each command is dispatched to its
+/// own `Handle` implementation, so there is no need to write a single `match`
+/// over the whole enum.
+///
+/// ```ignore
+/// #[derive(Command, Deserialize)]
+/// pub enum BankAccountCommand {
+///     Deposit(Deposit),
+///     Withdraw(Withdraw),
+/// }
+///
+/// impl Handle<Deposit> for BankAccount {
+///     type Error = BankAccountError;
+///
+///     fn handle(&self, cmd: Deposit) -> Result<Vec<BankAccountEvent>, Self::Error> {
+///         events![Deposited { amount: cmd.amount }]
+///     }
+/// }
+///
+/// impl Handle<Withdraw> for BankAccount {
+///     type Error = BankAccountError;
+///
+///     fn handle(&self, cmd: Withdraw) -> Result<Vec<BankAccountEvent>, Self::Error> {
+///         if cmd.amount > self.balance {
+///             return Err(BankAccountError::InsufficientFunds);
+///         }
+///
+///         events![Withdrawn { amount: cmd.amount }]
+///     }
+/// }
+/// ```
+///
+/// Errors from each handler must implement `Serialize`, and are returned to the
+/// caller as JSON.
+///
+/// # Generics
+///
+/// Generic parameters, bounds and where clauses on the enum are carried over
+/// to the generated implementations.
+///
+/// ```
+/// use thalo::{Aggregate, Command, Handle, State};
+///
+/// pub trait Clock {
+///     fn now() -> u64;
+/// }
+///
+/// pub struct Account {
+///     opened_at: Option<u64>,
+/// }
+///
+/// impl Aggregate for Account {
+///     type Command = AccountCommand<'static, FixedClock>;
+///     type Event = ();
+///
+///     fn init(_id: String) -> Self {
+///         Account { opened_at: None }
+///     }
+/// }
+///
+/// #[derive(Command)]
+/// pub enum AccountCommand<'a, C: Clock> {
+///     Open(Open<'a, C>),
+/// }
+///
+/// pub struct Open<'a, C> {
+///     name: &'a str,
+///     clock: std::marker::PhantomData<C>,
+/// }
+///
+/// impl<'a, C: Clock> Handle<Open<'a, C>> for Account {
+///     type Error = ();
+///
+///     fn handle(&self, cmd: Open<'a, C>) -> Result<Vec<()>, Self::Error> {
+///         assert_eq!(cmd.name, "savings");
+///         Ok(vec![])
+///     }
+/// }
+///
+/// pub struct FixedClock;
+///
+/// impl Clock for FixedClock {
+///     fn now() -> u64 {
+///         0
+///     }
+/// }
+///
+/// let state: State<Account> = State::init("abc".to_string());
+/// let cmd = AccountCommand::<FixedClock>::from(Open {
+///     name: "savings",
+///     clock: std::marker::PhantomData,
+/// });
+/// let events = Handle::<AccountCommand<FixedClock>>::handle(&state, cmd).unwrap();
+/// assert!(events.is_empty());
+/// ```
 #[proc_macro_derive(Command)]
 pub fn command(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     syn::parse_macro_input!(input as DeriveCommand)
@@ -29,6 +138,80 @@ pub fn command(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
 ///
 /// - Implements `thalo::Apply<...> for thalo::State<T>`.
 /// - Implements `From<#path> for #ident` for each variant.
+///
+/// # Renaming Events
+///
+/// Event names are matched by the enum's `serde` implementation, so a variant
+/// can be renamed without migrating persisted events by keeping the old name as
+/// an alias. Only the canonical name is written for new events, while both
+/// names are accepted when events are applied.
+///
+/// ```ignore
+/// #[derive(Event, Serialize, Deserialize)]
+/// pub enum CounterEvent {
+///     #[serde(rename = "CountIncremented", alias = "Incremented")]
+///     Incremented(Incremented),
+/// }
+/// ```
+///
+/// # Generics
+///
+/// Generic parameters, bounds and where clauses on the enum are carried over
+/// to the generated implementations.
+///
+/// ```
+/// use thalo::{Aggregate, Apply, Event, State};
+///
+/// pub trait Clock {
+///     fn now() -> u64;
+/// }
+///
+/// pub struct Account {
+///     opened_at: Option<u64>,
+/// }
+///
+/// impl Aggregate for Account {
+///     type Command = ();
+///     type Event = AccountEvent<'static, FixedClock>;
+///
+///     fn init(_id: String) -> Self {
+///         Account { opened_at: None }
+///     }
+/// }
+///
+/// #[derive(Event)]
+/// pub enum AccountEvent<'a, C: Clock> {
+///     Opened(Opened<'a, C>),
+/// }
+///
+/// pub struct Opened<'a, C> {
+///     name: &'a str,
+///     clock: std::marker::PhantomData<C>,
+/// }
+///
+/// impl<'a, C: Clock> Apply<Opened<'a, C>> for Account {
+///     fn apply(&mut self, event: Opened<'a, C>) {
+///         assert_eq!(event.name, "savings");
+///         self.opened_at = Some(C::now());
+///     }
+/// }
+///
+/// pub struct FixedClock;
+///
+/// impl Clock for FixedClock {
+///     fn now() -> u64 {
+///         42
+///     }
+/// }
+///
+/// let mut state: State<Account> = State::init("abc".to_string());
+/// let event = AccountEvent::<FixedClock>::from(Opened {
+///     name: "savings",
+///     clock: std::marker::PhantomData,
+/// });
+/// Apply::<AccountEvent<FixedClock>>::apply(&mut state, event);
+/// assert_eq!(state.0.opened_at, Some(42));
+/// ```
 #[proc_macro_derive(Event)]
 pub fn event(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     syn::parse_macro_input!(input as DeriveEvent)
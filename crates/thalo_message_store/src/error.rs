@@ -1,7 +1,9 @@
-use sled::transaction::ConflictableTransactionError;
+use sled::transaction::{ConflictableTransactionError, TransactionError};
 use thalo::stream_name::EmptyStreamName;
 use thiserror::Error;
 
+use crate::stream::ExpectedVersion;
+
 /// Type alias for `Result<T, message_db::Error>`
 pub type Result<T, E = Error> = std::result::Result<T, E>;
 
@@ -13,20 +15,28 @@ pub enum Error {
     Database(#[from] sled::Error),
     /// Database transaction error.
     #[error(transparent)]
-    DatabaseTransaction(
-        #[from] sled::transaction::TransactionError<ConflictableTransactionError<Box<Error>>>,
-    ),
+    DatabaseTransaction(#[from] TransactionError<ConflictableTransactionError<Box<Error>>>),
+
+    /// IO error.
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
 
     /// Message data failed to deserialize.
     #[error("failed to deserialize data: {0}")]
     DeserializeData(serde_cbor::Error),
 
+    #[error("failed to deserialize backup: {0}")]
+    DeserializeBackup(serde_json::Error),
+
     #[error("failed to deserialize projection: {0}")]
     DeserializeProjection(bincode::Error),
 
     #[error("failed to serialize data: {0}")]
     SerializeData(serde_cbor::Error),
 
+    #[error("failed to serialize backup: {0}")]
+    SerializeBackup(serde_json::Error),
+
     #[error("failed to serialize projection: {0}")]
     SerializeProjection(bincode::Error),
 
@@ -39,10 +49,104 @@ pub enum Error {
     #[error("invalid u64 ID")]
     InvalidU64Id,
 
+    #[error("cannot restore message at position {position} (Stream: {stream_name}, Stream Version: {stream_version:?})")]
+    RestoreConflict {
+        position: u64,
+        stream_name: String,
+        stream_version: Option<u64>,
+    },
+
+    #[error(
+        "stream {stream_name} is still being written to after {attempts} attempts to delete it"
+    )]
+    DeleteConflict {
+        stream_name: String,
+        attempts: usize,
+    },
+
     #[error("wrong expected version: {expected_version} (Stream: {stream_name}, Stream Version: {stream_version:?})")]
     WrongExpectedVersion {
-        expected_version: u64,
+        expected_version: ExpectedVersion,
         stream_name: String,
         stream_version: Option<u64>,
     },
 }
+
+/// A backend independent classification of an [`Error`].
+///
+/// This allows errors to be mapped to responses, such as gRPC statuses,
+/// without matching on each underlying database error.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ErrorCode {
+    /// The operation conflicts with existing data.
+    Conflict,
+    /// An optimistic concurrency check failed, and the operation may succeed if
+    /// retried.
+    Concurrency,
+    /// The requested data does not exist.
+    NotFound,
+    /// The database is unavailable.
+    Unavailable,
+    /// The input was invalid.
+    Invalid,
+    /// An unexpected internal error occured.
+    Internal,
+}
+
+impl Error {
+    /// Returns the [`ErrorCode`] for this error.
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            Error::Database(err) => sled_error_code(err),
+            Error::DatabaseTransaction(err) => match err {
+                TransactionError::Abort(ConflictableTransactionError::Abort(err)) => err.code(),
+                TransactionError::Abort(ConflictableTransactionError::Conflict) => {
+                    ErrorCode::Concurrency
+                }
+                TransactionError::Abort(ConflictableTransactionError::Storage(err))
+                | TransactionError::Storage(err) => sled_error_code(err),
+            },
+            Error::Io(_) => ErrorCode::Unavailable,
+            Error::DeserializeBackup(_) | Error::EmptyStreamName(_) => ErrorCode::Invalid,
+            Error::DeserializeData(_)
+            | Error::DeserializeProjection(_)
+            | Error::SerializeBackup(_)
+            | Error::SerializeData(_)
+            | Error::SerializeProjection(_)
+            | Error::InvalidEventReference { .. }
+            | Error::InvalidU64Id => ErrorCode::Internal,
+            Error::RestoreConflict { .. } => ErrorCode::Conflict,
+            Error::DeleteConflict { .. } | Error::WrongExpectedVersion { .. } => {
+                ErrorCode::Concurrency
+            }
+        }
+    }
+
+    /// Returns the expected version and the stream's version if this is a
+    /// wrong expected version error.
+    pub(crate) fn wrong_expected_version(&self) -> Option<(ExpectedVersion, Option<u64>)> {
+        let err = match self {
+            Error::DatabaseTransaction(TransactionError::Abort(
+                ConflictableTransactionError::Abort(err),
+            )) => err.as_ref(),
+            err => err,
+        };
+        match err {
+            Error::WrongExpectedVersion {
+                expected_version,
+                stream_version,
+                ..
+            } => Some((*expected_version, *stream_version)),
+            _ => None,
+        }
+    }
+}
+
+fn sled_error_code(err: &sled::Error) -> ErrorCode {
+    match err {
+        sled::Error::CollectionNotFound(_) => ErrorCode::NotFound,
+        sled::Error::Unsupported(_) => ErrorCode::Invalid,
+        sled::Error::Io(_) => ErrorCode::Unavailable,
+        sled::Error::ReportableBug(_) | sled::Error::Corruption { .. } => ErrorCode::Internal,
+    }
+}
@@ -1,6 +1,7 @@
 use std::ops;
 
-use sled::{Db, Tree};
+use sled::{Db, IVec, Tree};
+use thalo::stream_name::{Category, StreamName};
 
 use crate::error::{Error, Result};
 use crate::stream::RawMessage;
@@ -20,12 +21,38 @@ impl GlobalEventLog {
     }
 
     pub fn iter_all_messages(&self) -> GlobalEventLogIter {
-        GlobalEventLogIter::new(self.db.clone(), self.tree.iter())
+        GlobalEventLogIter::new(self.db.clone(), self.tree.iter(), None)
     }
 
+    /// Iterates messages across all streams, starting from `from_global_id`
+    /// (inclusive).
+    pub fn iter_messages_from(&self, from_global_id: u64) -> GlobalEventLogIter {
+        GlobalEventLogIter::new(
+            self.db.clone(),
+            self.tree.range(from_global_id.to_be_bytes()..),
+            None,
+        )
+    }
+
+    /// Iterates messages across all streams in a category, ordered by global
+    /// id and starting from `from_global_id` (inclusive).
+    ///
+    /// This is the building block for category projections, which need every
+    /// event for an entity type regardless of which stream it was written to.
+    pub fn iter_category(&self, category: Category<'_>, from_global_id: u64) -> GlobalEventLogIter {
+        GlobalEventLogIter::new(
+            self.db.clone(),
+            self.tree.range(from_global_id.to_be_bytes()..),
+            Some(category.into_string()),
+        )
+    }
+
+    /// Returns the message with the given global id, or `None` if it does not
+    /// exist or its stream has been deleted.
     pub fn get(&self, id: u64) -> Result<Option<RawMessage<()>>> {
         self.tree
             .get(id.to_be_bytes())?
+            .filter(|value| !value.is_empty())
             .map(|value| {
                 let (stream_key, stream_name) = value.split_at(8);
                 let tree = self.db.open_tree(stream_name)?;
@@ -61,11 +88,42 @@ impl ops::Deref for GlobalEventLog {
 pub struct GlobalEventLogIter {
     db: Db,
     inner: sled::Iter,
+    category: Option<String>,
 }
 
 impl GlobalEventLogIter {
-    fn new(db: Db, inner: sled::Iter) -> Self {
-        GlobalEventLogIter { db, inner }
+    fn new(db: Db, inner: sled::Iter, category: Option<String>) -> Self {
+        GlobalEventLogIter {
+            db,
+            inner,
+            category,
+        }
+    }
+
+    fn in_category(&self, stream_name: &[u8]) -> bool {
+        let Some(category) = &self.category else {
+            return true;
+        };
+
+        match stream_name.strip_prefix(category.as_bytes()) {
+            Some(rest) => rest.is_empty() || rest[0] == StreamName::ID_SEPARATOR as u8,
+            None => false,
+        }
+    }
+
+    fn message(&self, global_id: IVec, id: IVec) -> Result<RawMessage<()>> {
+        let (id, stream_name) = id.split_at(8);
+        let tree = self.db.open_tree(stream_name)?;
+        let message = tree.get(id)?.ok_or_else(|| {
+            let id = id
+                .try_into()
+                .map(|id| u64::from_be_bytes(id))
+                .unwrap_or_default();
+            let stream_name = String::from_utf8_lossy(stream_name).into_owned();
+            Error::InvalidEventReference { id, stream_name }
+        })?;
+
+        Ok(RawMessage::new(global_id, message))
     }
 }
 
@@ -73,21 +131,17 @@ impl Iterator for GlobalEventLogIter {
     type Item = Result<RawMessage<()>>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.inner.next().map(|res| {
-            res.map_err(Error::from).and_then(|(global_id, id)| {
-                let (id, stream_name) = id.split_at(8);
-                let tree = self.db.open_tree(stream_name)?;
-                let message = tree.get(id)?.ok_or_else(|| {
-                    let id = id
-                        .try_into()
-                        .map(|id| u64::from_be_bytes(id))
-                        .unwrap_or_default();
-                    let stream_name = String::from_utf8_lossy(stream_name).into_owned();
-                    Error::InvalidEventReference { id, stream_name }
-                })?;
-
-                Ok(RawMessage::new(global_id, message))
-            })
-        })
+        loop {
+            let (global_id, id) = match self.inner.next()? {
+                Ok(entry) => entry,
+                Err(err) => return Some(Err(err.into())),
+            };
+            // Empty references are tombstones left by deleted streams.
+            if id.len() < 8 || !self.in_category(&id[8..]) {
+                continue;
+            }
+
+            return Some(self.message(global_id, id));
+        }
     }
 }
@@ -75,6 +75,20 @@ impl<'a, T> Message<'a, T> {
         serde_json::from_value(json!({ msg_type: self.data }))
     }
 
+    /// Deserializes a single field from the message data, using a JSON pointer
+    /// such as `"/amount"`.
+    ///
+    /// This avoids deserializing the full event when only a few fields are
+    /// needed. Returns `None` if the field is missing or has a different type.
+    pub fn get_field<F>(&self, pointer: &str) -> Option<F>
+    where
+        F: DeserializeOwned,
+    {
+        self.data
+            .pointer(pointer)
+            .and_then(|value| F::deserialize(value).ok())
+    }
+
     pub fn as_event_type<U>(self) -> Message<'a, U> {
         Message {
             id: self.id,
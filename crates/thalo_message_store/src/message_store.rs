@@ -1,14 +1,19 @@
+use std::borrow::Cow;
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::io::{BufRead, Write};
 use std::path::Path;
 
 use sled::{Db, Mode};
 use thalo::stream_name::{Category, StreamName};
 
-use crate::error::Result;
+use crate::error::{Error, Result};
 use crate::global_event_log::GlobalEventLog;
 use crate::id_generator::IdGenerator;
+use crate::message::Message;
 use crate::outbox::Outbox;
 use crate::projection::{Projection, PROJECTION_POSITIONS_TREE};
-use crate::stream::Stream;
+use crate::stream::{ExpectedVersion, Stream};
 
 #[derive(Clone)]
 pub struct MessageStore {
@@ -65,4 +70,77 @@ impl MessageStore {
         let outbox = Outbox::new(tree);
         Ok(outbox)
     }
+
+    /// Writes all messages after the global id `since` to `writer` as JSON
+    /// lines, in global order.
+    ///
+    /// Returns the global id of the last message written, which can be passed
+    /// as `since` for the next incremental backup. If no messages were written,
+    /// `since` is returned.
+    pub fn backup(&self, mut writer: impl Write, since: Option<u64>) -> Result<Option<u64>> {
+        let Some(from) = since.map_or(Some(0), |global_id| global_id.checked_add(1)) else {
+            // Nothing can come after the last possible global id.
+            return Ok(since);
+        };
+        let mut last_global_id = since;
+        for res in self.global_event_log()?.iter_messages_from(from) {
+            let raw_message = res?;
+            let message = raw_message.message()?;
+            serde_json::to_writer(&mut writer, &message).map_err(Error::SerializeBackup)?;
+            writer.write_all(b"\n")?;
+            last_global_id = Some(message.global_id);
+        }
+        writer.flush()?;
+
+        Ok(last_global_id)
+    }
+
+    /// Restores messages written by [`MessageStore::backup`], appending them to
+    /// their streams in order.
+    ///
+    /// Messages are written as new messages, so they are assigned new ids and
+    /// timestamps. Each message's position is used as the expected version, so
+    /// restoring into streams which already contain messages fails rather
+    /// than producing duplicates.
+    ///
+    /// Returns the number of messages restored.
+    pub fn restore(&self, reader: impl BufRead) -> Result<usize> {
+        // Streams are kept open so their versions stay cached between messages.
+        let mut streams = HashMap::new();
+        let mut count = 0;
+        for line in reader.lines() {
+            let line = line?;
+            if line.is_empty() {
+                continue;
+            }
+
+            let message: Message<'static> =
+                serde_json::from_str(&line).map_err(Error::DeserializeBackup)?;
+            let stream = match streams.entry(message.stream_name.clone()) {
+                Entry::Occupied(entry) => entry.into_mut(),
+                Entry::Vacant(entry) => {
+                    let stream = self.stream(entry.key().clone())?;
+                    entry.insert(stream)
+                }
+            };
+
+            let expected_version = ExpectedVersion::from(message.position.checked_sub(1));
+            stream
+                .write_messages(
+                    &[(&message.msg_type, Cow::Borrowed(&message.data))],
+                    expected_version,
+                )
+                .map_err(|err| match err.wrong_expected_version() {
+                    Some((_, stream_version)) => Error::RestoreConflict {
+                        position: message.position,
+                        stream_name: message.stream_name.to_string(),
+                        stream_version,
+                    },
+                    None => err,
+                })?;
+            count += 1;
+        }
+
+        Ok(count)
+    }
 }
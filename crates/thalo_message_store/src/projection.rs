@@ -67,25 +67,29 @@ impl Projection {
     }
 
     pub fn acknowledge_event(&mut self, position: u64, is_relevant: bool) -> Result<()> {
-        let new_last_seen_event_id = position;
         let new_last_relevant_event_id = if is_relevant {
             Some(position)
         } else {
             self.last_relevant_event_id
         };
 
-        let key = self.id.to_be_bytes();
-        let value = bincode::serialize(&ProjectionData {
-            name: &self.name,
-            last_seen_event_id: new_last_seen_event_id,
-            last_relevant_event_id: new_last_relevant_event_id,
-        })
-        .map_err(Error::SerializeProjection)?;
-        self.tree.insert(key, value)?;
-        self.last_seen_event_id = Some(new_last_seen_event_id);
-        self.last_relevant_event_id = new_last_relevant_event_id;
+        self.save_position(position, new_last_relevant_event_id)
+    }
 
-        Ok(())
+    /// Moves the projection to an arbitrary global id, as if every event up to
+    /// and including `global_id` had been acknowledged.
+    ///
+    /// This can be used to skip events, or to rewind a projection to rebuild
+    /// part of a read model. Use [`Projection::reset_position`] to start again
+    /// from the beginning.
+    ///
+    /// Running projections overwrite their position as they acknowledge
+    /// events, so this only takes effect on projections which aren't running.
+    /// The runtime moves running projections by closing their subscription
+    /// first.
+    pub fn set_position(&mut self, global_id: u64) -> Result<()> {
+        // Subscriptions resume after the last relevant event, so it's moved too.
+        self.save_position(global_id, Some(global_id))
     }
 
     pub fn reset_position(&mut self) -> Result<()> {
@@ -95,6 +99,25 @@ impl Projection {
 
         Ok(())
     }
+
+    fn save_position(
+        &mut self,
+        last_seen_event_id: u64,
+        last_relevant_event_id: Option<u64>,
+    ) -> Result<()> {
+        let key = self.id.to_be_bytes();
+        let value = bincode::serialize(&ProjectionData {
+            name: &self.name,
+            last_seen_event_id,
+            last_relevant_event_id,
+        })
+        .map_err(Error::SerializeProjection)?;
+        self.tree.insert(key, value)?;
+        self.last_seen_event_id = Some(last_seen_event_id);
+        self.last_relevant_event_id = last_relevant_event_id;
+
+        Ok(())
+    }
 }
 
 impl ops::Deref for Projection {
@@ -1,7 +1,7 @@
 use std::borrow::Cow;
 use std::marker::PhantomData;
-use std::ops;
-use std::time::SystemTime;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use std::{fmt, ops};
 
 use sled::transaction::{ConflictableTransactionError, Transactional, TransactionalTree};
 use sled::{IVec, Tree};
@@ -13,6 +13,14 @@ use crate::global_event_log::GlobalEventLog;
 use crate::id_generator::IdGenerator;
 use crate::message::Message;
 
+/// Maximum number of times [`Stream::write_messages_or_resolve`] calls its
+/// resolver before giving up.
+pub const MAX_RESOLVE_ATTEMPTS: usize = 10;
+
+/// Maximum number of attempts [`Stream::delete`] makes to empty a stream which
+/// is being written to.
+pub const MAX_DELETE_ATTEMPTS: usize = 10;
+
 #[derive(Clone)]
 pub struct Stream<'a> {
     id_generator: IdGenerator,
@@ -46,10 +54,59 @@ impl<'a> Stream<'a> {
         MessageIter::new(self.tree.iter())
     }
 
+    /// Iterates messages with positions between `from` and `to` (inclusive).
+    ///
+    /// Nothing is returned if `from` is greater than `to`. This is useful for
+    /// rebuilding an aggregate as it was at a previous version.
+    ///
+    /// Messages aren't keyed by position, so the `from` messages before the
+    /// range are read and skipped, making this O(`from`) before the first
+    /// message is returned.
+    pub fn iter_messages_range<T>(
+        &self,
+        from: u64,
+        to: u64,
+    ) -> impl Iterator<Item = Result<RawMessage<T>>> {
+        // Saturating, as a range of every position is one more than `u64::MAX`.
+        let count = to
+            .checked_sub(from)
+            .map(|n| n.saturating_add(1))
+            .unwrap_or(0);
+        MessageIter::new(self.tree.iter())
+            .skip(usize::try_from(from).unwrap_or(usize::MAX))
+            .take(usize::try_from(count).unwrap_or(usize::MAX))
+    }
+
+    /// Iterates messages written at or after `since`, in position order.
+    ///
+    /// Message times are stored with millisecond precision, so `since` is
+    /// truncated to the millisecond. Messages aren't indexed by time, so every
+    /// message in the stream is read to check its timestamp.
+    pub fn iter_messages_since<T>(
+        &self,
+        since: SystemTime,
+    ) -> impl Iterator<Item = Result<RawMessage<T>>> {
+        let since_millis = since
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_millis())
+            .unwrap_or(0);
+        let since = UNIX_EPOCH + Duration::from_millis(since_millis as u64);
+        MessageIter::new(self.tree.iter()).filter(move |res| match res {
+            Ok(raw_message) => raw_message
+                .message()
+                .map(|message| message.time >= since)
+                .unwrap_or(true),
+            Err(_) => true,
+        })
+    }
+
+    /// Writes messages to the stream in a single transaction, failing with
+    /// [`Error::WrongExpectedVersion`] if the stream's version doesn't match
+    /// the expected version.
     pub fn write_messages<'b>(
         &'b mut self,
         messages: &[(&'b str, Cow<'b, serde_json::Value>)],
-        expected_starting_version: Option<u64>,
+        expected_version: ExpectedVersion,
     ) -> Result<Vec<Message<'b>>>
     where
         'a: 'b,
@@ -58,7 +115,7 @@ impl<'a> Stream<'a> {
             return Ok(vec![]);
         }
 
-        let stream_version = self.version();
+        let stream_version = self.version()?;
 
         let (written_messages, new_version) = (&self.tree, &*self.global_event_log).transaction(
             |(tx_stream, tx_global_event_log)| {
@@ -66,14 +123,12 @@ impl<'a> Stream<'a> {
                 let mut stream_version = stream_version;
 
                 for (i, (msg_type, data)) in messages.iter().enumerate() {
+                    // Only the first message needs checking, as the following
+                    // messages are written after it in the same transaction.
                     let expected_version = if i == 0 {
-                        expected_starting_version.map(|ev| ev + i as u64)
+                        expected_version
                     } else {
-                        Some(
-                            expected_starting_version
-                                .map(|ev| ev + i as u64)
-                                .unwrap_or(i as u64 - 1),
-                        )
+                        ExpectedVersion::Any
                     };
                     let global_id = self.id_generator.generate_id();
                     let written_message = Self::write_message_in_tx(
@@ -103,6 +158,74 @@ impl<'a> Stream<'a> {
         Ok(written_messages)
     }
 
+    /// Writes messages, calling `resolve` with the messages written since the
+    /// expected version if the expected version is wrong.
+    ///
+    /// This allows conflicts to be merged rather than failing, such as when the
+    /// new messages are independent of the messages written in the meantime.
+    /// The resolver is called again for each conflict until the messages are
+    /// written or it returns [`ResolveAction::Abort`]. After
+    /// [`MAX_RESOLVE_ATTEMPTS`] conflicts, the wrong expected version error is
+    /// returned without calling the resolver again.
+    pub fn write_messages_or_resolve<'b, F>(
+        &mut self,
+        messages: &[(&'b str, Cow<'b, serde_json::Value>)],
+        expected_version: ExpectedVersion,
+        mut resolve: F,
+    ) -> Result<Vec<Message<'static>>>
+    where
+        'a: 'b,
+        F: FnMut(&[Message<'_>]) -> ResolveAction<'b>,
+    {
+        let mut messages = Cow::Borrowed(messages);
+        let mut expected_version = expected_version;
+        let mut attempts = 0;
+        loop {
+            let err = match self.write_messages(&messages, expected_version) {
+                Ok(written_messages) => {
+                    return Ok(written_messages
+                        .into_iter()
+                        .map(Message::into_owned)
+                        .collect())
+                }
+                Err(err) => err,
+            };
+            let Some((_, stream_version)) = err.wrong_expected_version() else {
+                return Err(err);
+            };
+            if attempts == MAX_RESOLVE_ATTEMPTS {
+                return Err(err);
+            }
+            attempts += 1;
+
+            let from = match expected_version {
+                ExpectedVersion::Exact(version) => version + 1,
+                ExpectedVersion::Any | ExpectedVersion::NoStream => 0,
+            };
+            let conflicting_messages = match stream_version {
+                Some(stream_version) => self
+                    .iter_messages_range::<()>(from, stream_version)
+                    .collect::<Result<Vec<_>>>()?,
+                None => vec![],
+            };
+            let conflicting_messages = conflicting_messages
+                .iter()
+                .map(RawMessage::message)
+                .collect::<Result<Vec<_>>>()?;
+
+            match resolve(&conflicting_messages) {
+                ResolveAction::Abort => return Err(err),
+                ResolveAction::RetryAt(version) => {
+                    expected_version = version;
+                }
+                ResolveAction::Rebase(new_messages) => {
+                    messages = Cow::Owned(new_messages);
+                    expected_version = ExpectedVersion::from(stream_version);
+                }
+            }
+        }
+    }
+
     fn write_message_in_tx<'b>(
         tx_stream: &TransactionalTree,
         tx_global_event_log: &TransactionalTree,
@@ -111,21 +234,16 @@ impl<'a> Stream<'a> {
         stream_version: Option<u64>,
         msg_type: &'b str,
         data: Cow<'b, serde_json::Value>,
-        expected_version: Option<u64>,
+        expected_version: ExpectedVersion,
     ) -> Result<Message<'b>, ConflictableTransactionError<Box<Error>>> {
-        if let Some(expected_version) = expected_version {
-            if stream_version
-                .map(|stream_version| expected_version != stream_version)
-                .unwrap_or(true)
-            {
-                return Err(ConflictableTransactionError::Abort(Box::new(
-                    Error::WrongExpectedVersion {
-                        expected_version,
-                        stream_name: stream_name.to_string(),
-                        stream_version,
-                    },
-                )));
-            }
+        if !expected_version.matches(stream_version) {
+            return Err(ConflictableTransactionError::Abort(Box::new(
+                Error::WrongExpectedVersion {
+                    expected_version,
+                    stream_name: stream_name.to_string(),
+                    stream_version,
+                },
+            )));
         }
 
         let next_position = stream_version
@@ -157,23 +275,106 @@ impl<'a> Stream<'a> {
         Ok(message)
     }
 
+    /// Deletes all messages in the stream, returning the number of messages
+    /// deleted.
+    ///
+    /// References in the global event log are replaced with tombstones rather
+    /// than removed, so global ids are never reused and readers of the global
+    /// event log skip the deleted messages. If new messages are written to the
+    /// stream afterwards, positions start again from `0`.
+    ///
+    /// Copies of the messages outside of the stream and global event log are
+    /// kept. This includes messages in the category's outbox which haven't
+    /// been relayed yet, and messages already relayed to other systems, so
+    /// erasing personal data must cover those separately.
+    ///
+    /// Messages written while the stream is being deleted are deleted too. If
+    /// the stream is still being written to after [`MAX_DELETE_ATTEMPTS`]
+    /// attempts, [`Error::DeleteConflict`] is returned, and the messages
+    /// deleted so far stay deleted.
+    ///
+    /// Other handles to the stream keep their cached version, and must not be
+    /// written to after the stream is deleted. While a runtime is running, its
+    /// streams should be deleted through the runtime, which evicts the cached
+    /// aggregate first.
+    pub fn delete(&mut self) -> Result<usize> {
+        let mut deleted = 0;
+        // Transactions can't iterate a tree, so the keys are read beforehand.
+        // Messages written in the meantime are deleted by the next attempt.
+        for _ in 0..MAX_DELETE_ATTEMPTS {
+            let keys = self.tree.iter().keys().collect::<Result<Vec<_>, _>>()?;
+            if keys.is_empty() {
+                self.version = Some(None);
+                return Ok(deleted);
+            }
+
+            deleted += (&self.tree, &*self.global_event_log).transaction(
+                |(tx_stream, tx_global_event_log)| {
+                    let mut deleted = 0;
+                    for key in &keys {
+                        let is_deleted =
+                            Self::delete_message_in_tx(tx_stream, tx_global_event_log, key)
+                                .map_err(ConflictableTransactionError::Abort)?;
+                        if is_deleted {
+                            deleted += 1;
+                        }
+                    }
+
+                    Ok(deleted)
+                },
+            )?;
+        }
+
+        self.version = None;
+
+        Err(Error::DeleteConflict {
+            stream_name: self.stream_name.to_string(),
+            attempts: MAX_DELETE_ATTEMPTS,
+        })
+    }
+
+    /// Removes a message from the stream, replacing its reference in the global
+    /// event log with a tombstone. Returns `false` if the message was already
+    /// removed.
+    fn delete_message_in_tx(
+        tx_stream: &TransactionalTree,
+        tx_global_event_log: &TransactionalTree,
+        key: &IVec,
+    ) -> Result<bool, ConflictableTransactionError<Box<Error>>> {
+        let Some(value) = tx_stream.remove(key)? else {
+            return Ok(false);
+        };
+        let global_id = RawMessage::<()>::new(key.clone(), value)
+            .message()
+            .map_err(|err| ConflictableTransactionError::Abort(Box::new(err)))?
+            .global_id;
+        tx_global_event_log.insert(&global_id.to_be_bytes(), Vec::new())?;
+
+        Ok(true)
+    }
+
     /// Returns the highest position number in the stream.
-    pub fn version(&mut self) -> Option<u64> {
+    ///
+    /// The version is read from the last message in the stream, and cached for
+    /// subsequent calls on this handle.
+    pub fn version(&mut self) -> Result<Option<u64>> {
         match self.version {
-            Some(version) => version,
+            Some(version) => Ok(version),
             None => {
-                let version = self.calculate_latest_version();
+                let version = self.calculate_latest_version()?;
                 self.version = Some(version);
-                version
+                Ok(version)
             }
         }
     }
 
-    fn calculate_latest_version(&self) -> Option<u64> {
-        match self.len() {
-            0 => None,
-            i => Some(i as u64 - 1),
-        }
+    /// Reads the position of the last message, without scanning the stream.
+    fn calculate_latest_version(&self) -> Result<Option<u64>> {
+        let Some((key, value)) = self.tree.last()? else {
+            return Ok(None);
+        };
+        let raw_message = RawMessage::<()>::new(key, value);
+        Ok(Some(raw_message.message()?.position))
     }
 }
 
@@ -185,6 +386,62 @@ impl ops::Deref for Stream<'_> {
     }
 }
 
+/// The version a stream is expected to be at when writing messages, used for
+/// optimistic concurrency control.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ExpectedVersion {
+    /// Write regardless of the stream's version.
+    Any,
+    /// The stream must not have any messages.
+    NoStream,
+    /// The stream's last message must be at the given position.
+    Exact(u64),
+}
+
+impl ExpectedVersion {
+    /// Returns whether a stream at `stream_version` satisfies the expected
+    /// version.
+    pub fn matches(self, stream_version: Option<u64>) -> bool {
+        match self {
+            ExpectedVersion::Any => true,
+            ExpectedVersion::NoStream => stream_version.is_none(),
+            ExpectedVersion::Exact(version) => stream_version == Some(version),
+        }
+    }
+}
+
+/// Converts a stream version, where `None` is [`ExpectedVersion::NoStream`].
+impl From<Option<u64>> for ExpectedVersion {
+    fn from(version: Option<u64>) -> Self {
+        match version {
+            Some(version) => ExpectedVersion::Exact(version),
+            None => ExpectedVersion::NoStream,
+        }
+    }
+}
+
+impl fmt::Display for ExpectedVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExpectedVersion::Any => write!(f, "any"),
+            ExpectedVersion::NoStream => write!(f, "no stream"),
+            ExpectedVersion::Exact(version) => write!(f, "{version}"),
+        }
+    }
+}
+
+/// The action to take when writing messages with
+/// [`Stream::write_messages_or_resolve`] conflicts.
+pub enum ResolveAction<'b> {
+    /// Fail with the wrong expected version error.
+    Abort,
+    /// Write the same messages again, expecting the given version.
+    RetryAt(ExpectedVersion),
+    /// Write different messages instead, expecting the stream's current
+    /// version.
+    Rebase(Vec<(&'b str, Cow<'b, serde_json::Value>)>),
+}
+
 #[derive(Clone)]
 pub struct RawMessage<T> {
     pub key: IVec,
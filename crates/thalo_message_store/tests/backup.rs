@@ -0,0 +1,122 @@
+use std::borrow::Cow;
+
+use serde_json::json;
+use thalo::stream_name::StreamName;
+use thalo_message_store::error::Error;
+use thalo_message_store::message::Message;
+use thalo_message_store::stream::ExpectedVersion;
+use thalo_message_store::MessageStore;
+
+fn open_store() -> MessageStore {
+    let db = sled::Config::new().temporary(true).open().unwrap();
+    MessageStore::new(db).unwrap()
+}
+
+fn write(store: &MessageStore, stream_name: &str, msg_type: &str, amount: u64) {
+    let data = json!({ "amount": amount });
+    store
+        .stream(StreamName::new(stream_name).unwrap())
+        .unwrap()
+        .write_messages(&[(msg_type, Cow::Owned(data))], ExpectedVersion::Any)
+        .unwrap();
+}
+
+/// Returns the stream name, position, type and data of each backed up message,
+/// which are the fields kept when restoring.
+fn backed_up_messages(store: &MessageStore) -> Vec<(String, u64, String, serde_json::Value)> {
+    let mut backup = Vec::new();
+    store.backup(&mut backup, None).unwrap();
+
+    String::from_utf8(backup)
+        .unwrap()
+        .lines()
+        .map(|line| {
+            let message: Message<'static> = serde_json::from_str(line).unwrap();
+            (
+                message.stream_name.to_string(),
+                message.position,
+                message.msg_type.into_owned(),
+                message.data.into_owned(),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn backup_and_restore_round_trip() {
+    let store = open_store();
+    write(&store, "counter-a", "Incremented", 1);
+    write(&store, "counter-b", "Incremented", 2);
+    write(&store, "counter-a", "Decremented", 3);
+
+    let mut backup = Vec::new();
+    let last_global_id = store.backup(&mut backup, None).unwrap();
+    assert_eq!(last_global_id, Some(2));
+
+    let restored_store = open_store();
+    assert_eq!(restored_store.restore(&backup[..]).unwrap(), 3);
+    assert_eq!(
+        backed_up_messages(&restored_store),
+        backed_up_messages(&store)
+    );
+}
+
+#[test]
+fn incremental_backup() {
+    let store = open_store();
+    write(&store, "counter-a", "Incremented", 1);
+    write(&store, "counter-a", "Incremented", 2);
+
+    let mut backup = Vec::new();
+    let since = store.backup(&mut backup, None).unwrap();
+
+    write(&store, "counter-a", "Incremented", 3);
+    let mut incremental_backup = Vec::new();
+    let last_global_id = store.backup(&mut incremental_backup, since).unwrap();
+    assert_eq!(last_global_id, Some(2));
+
+    let restored_store = open_store();
+    restored_store.restore(&backup[..]).unwrap();
+    assert_eq!(restored_store.restore(&incremental_backup[..]).unwrap(), 1);
+    assert_eq!(
+        backed_up_messages(&restored_store),
+        backed_up_messages(&store)
+    );
+}
+
+#[test]
+fn backup_since_last_global_id_is_empty() {
+    let store = open_store();
+    write(&store, "counter-a", "Incremented", 1);
+
+    let mut backup = Vec::new();
+    let last_global_id = store.backup(&mut backup, Some(u64::MAX)).unwrap();
+    assert_eq!(last_global_id, Some(u64::MAX));
+    assert!(backup.is_empty());
+}
+
+#[test]
+fn restore_into_conflicting_stream() {
+    let store = open_store();
+    write(&store, "counter-a", "Incremented", 1);
+    write(&store, "counter-a", "Incremented", 2);
+
+    let mut backup = Vec::new();
+    store.backup(&mut backup, None).unwrap();
+
+    let restored_store = open_store();
+    write(&restored_store, "counter-a", "Incremented", 3);
+
+    let err = restored_store.restore(&backup[..]).unwrap_err();
+    assert!(
+        matches!(
+            &err,
+            Error::RestoreConflict {
+                position: 0,
+                stream_name,
+                stream_version: Some(0),
+            } if stream_name == "counter-a"
+        ),
+        "{err}"
+    );
+}
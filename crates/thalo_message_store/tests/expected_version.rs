@@ -0,0 +1,125 @@
+use std::borrow::Cow;
+
+use serde_json::json;
+use sled::transaction::{ConflictableTransactionError, TransactionError};
+use thalo::stream_name::StreamName;
+use thalo_message_store::error::{Error, ErrorCode};
+use thalo_message_store::stream::{ExpectedVersion, Stream};
+use thalo_message_store::MessageStore;
+
+fn open_store() -> MessageStore {
+    let db = sled::Config::new().temporary(true).open().unwrap();
+    MessageStore::new(db).unwrap()
+}
+
+fn write(stream: &mut Stream<'_>, expected_version: ExpectedVersion) -> Result<u64, Error> {
+    let messages = stream.write_messages(
+        &[("Incremented", Cow::Owned(json!({ "amount": 1 })))],
+        expected_version,
+    )?;
+    Ok(messages[0].position)
+}
+
+/// Asserts that `err` is a wrong expected version error, which is returned
+/// from within the write transaction.
+fn assert_wrong_expected_version(
+    err: Error,
+    expected: ExpectedVersion,
+    expected_stream_version: Option<u64>,
+) {
+    assert_eq!(err.code(), ErrorCode::Concurrency);
+    let Error::DatabaseTransaction(TransactionError::Abort(ConflictableTransactionError::Abort(
+        err,
+    ))) = err
+    else {
+        panic!("expected a transaction abort, got {err}");
+    };
+    match *err {
+        Error::WrongExpectedVersion {
+            expected_version,
+            stream_name,
+            stream_version,
+        } => {
+            assert_eq!(expected_version, expected);
+            assert_eq!(stream_name, "counter-a");
+            assert_eq!(stream_version, expected_stream_version);
+        }
+        err => panic!("expected a wrong expected version error, got {err}"),
+    }
+}
+
+#[test]
+fn any_always_writes() {
+    let store = open_store();
+    let mut stream = store.stream(StreamName::new("counter-a").unwrap()).unwrap();
+
+    assert_eq!(write(&mut stream, ExpectedVersion::Any).unwrap(), 0);
+    assert_eq!(write(&mut stream, ExpectedVersion::Any).unwrap(), 1);
+    assert_eq!(stream.version().unwrap(), Some(1));
+}
+
+#[test]
+fn no_stream_writes_to_empty_stream() {
+    let store = open_store();
+    let mut stream = store.stream(StreamName::new("counter-a").unwrap()).unwrap();
+
+    assert_eq!(write(&mut stream, ExpectedVersion::NoStream).unwrap(), 0);
+    assert_eq!(stream.version().unwrap(), Some(0));
+}
+
+#[test]
+fn no_stream_conflicts_with_existing_stream() {
+    let store = open_store();
+    let mut stream = store.stream(StreamName::new("counter-a").unwrap()).unwrap();
+    write(&mut stream, ExpectedVersion::Any).unwrap();
+
+    let err = write(&mut stream, ExpectedVersion::NoStream).unwrap_err();
+    assert_wrong_expected_version(err, ExpectedVersion::NoStream, Some(0));
+    assert_eq!(stream.version().unwrap(), Some(0));
+}
+
+#[test]
+fn exact_writes_at_stream_version() {
+    let store = open_store();
+    let mut stream = store.stream(StreamName::new("counter-a").unwrap()).unwrap();
+    write(&mut stream, ExpectedVersion::Any).unwrap();
+
+    assert_eq!(write(&mut stream, ExpectedVersion::Exact(0)).unwrap(), 1);
+    assert_eq!(write(&mut stream, ExpectedVersion::Exact(1)).unwrap(), 2);
+}
+
+#[test]
+fn exact_conflicts_with_other_stream_version() {
+    let store = open_store();
+    let mut stream = store.stream(StreamName::new("counter-a").unwrap()).unwrap();
+
+    let err = write(&mut stream, ExpectedVersion::Exact(0)).unwrap_err();
+    assert_wrong_expected_version(err, ExpectedVersion::Exact(0), None);
+
+    write(&mut stream, ExpectedVersion::Any).unwrap();
+    write(&mut stream, ExpectedVersion::Any).unwrap();
+
+    let err = write(&mut stream, ExpectedVersion::Exact(0)).unwrap_err();
+    assert_wrong_expected_version(err, ExpectedVersion::Exact(0), Some(1));
+    let err = write(&mut stream, ExpectedVersion::Exact(2)).unwrap_err();
+    assert_wrong_expected_version(err, ExpectedVersion::Exact(2), Some(1));
+    assert_eq!(stream.version().unwrap(), Some(1));
+}
+
+#[test]
+fn from_stream_version() {
+    assert_eq!(ExpectedVersion::from(None), ExpectedVersion::NoStream);
+    assert_eq!(ExpectedVersion::from(Some(0)), ExpectedVersion::Exact(0));
+    assert_eq!(ExpectedVersion::from(Some(5)), ExpectedVersion::Exact(5));
+}
+
+#[test]
+fn matches() {
+    assert!(ExpectedVersion::Any.matches(None));
+    assert!(ExpectedVersion::Any.matches(Some(3)));
+    assert!(ExpectedVersion::NoStream.matches(None));
+    assert!(!ExpectedVersion::NoStream.matches(Some(0)));
+    assert!(ExpectedVersion::Exact(3).matches(Some(3)));
+    assert!(!ExpectedVersion::Exact(3).matches(Some(2)));
+    assert!(!ExpectedVersion::Exact(0).matches(None));
+}
@@ -0,0 +1,100 @@
+use std::borrow::Cow;
+
+use serde_json::json;
+use thalo::stream_name::{Category, StreamName};
+use thalo_message_store::stream::ExpectedVersion;
+use thalo_message_store::MessageStore;
+
+fn open_store() -> MessageStore {
+    let db = sled::Config::new().temporary(true).open().unwrap();
+    MessageStore::new(db).unwrap()
+}
+
+fn write(store: &MessageStore, stream_name: &str) {
+    store
+        .stream(StreamName::new(stream_name).unwrap())
+        .unwrap()
+        .write_messages(
+            &[("Incremented", Cow::Owned(json!({ "amount": 1 })))],
+            ExpectedVersion::Any,
+        )
+        .unwrap();
+}
+
+/// Returns the global id and stream name of each message in the category.
+fn iter_category(store: &MessageStore, category: &str, from: u64) -> Vec<(u64, String)> {
+    store
+        .global_event_log()
+        .unwrap()
+        .iter_category(Category::new(category).unwrap(), from)
+        .map(|res| {
+            let raw_message = res.unwrap();
+            let message = raw_message.message().unwrap();
+            (message.global_id, message.stream_name.to_string())
+        })
+        .collect()
+}
+
+#[test]
+fn iter_category_filters_streams() {
+    let store = open_store();
+    write(&store, "counter-a"); // 0
+    write(&store, "account-a"); // 1
+    write(&store, "counter-b"); // 2
+    write(&store, "counterx-a"); // 3
+    write(&store, "counter:command-a"); // 4
+    write(&store, "counter"); // 5
+
+    assert_eq!(
+        iter_category(&store, "counter", 0),
+        [
+            (0, "counter-a".to_string()),
+            (2, "counter-b".to_string()),
+            (5, "counter".to_string()),
+        ]
+    );
+    assert_eq!(
+        iter_category(&store, "counter:command", 0),
+        [(4, "counter:command-a".to_string())]
+    );
+    assert!(iter_category(&store, "missing", 0).is_empty());
+}
+
+#[test]
+fn iter_category_from_global_id() {
+    let store = open_store();
+    write(&store, "counter-a"); // 0
+    write(&store, "account-a"); // 1
+    write(&store, "counter-b"); // 2
+    write(&store, "counter-a"); // 3
+
+    assert_eq!(
+        iter_category(&store, "counter", 2),
+        [(2, "counter-b".to_string()), (3, "counter-a".to_string())]
+    );
+    assert_eq!(
+        iter_category(&store, "counter", 3),
+        [(3, "counter-a".to_string())]
+    );
+    assert!(iter_category(&store, "counter", 4).is_empty());
+    assert!(iter_category(&store, "counter", u64::MAX).is_empty());
+}
+
+#[test]
+fn iter_category_skips_deleted_streams() {
+    let store = open_store();
+    write(&store, "counter-a"); // 0
+    write(&store, "counter-b"); // 1
+    write(&store, "counter-a"); // 2
+
+    store
+        .stream(StreamName::new("counter-a").unwrap())
+        .unwrap()
+        .delete()
+        .unwrap();
+
+    assert_eq!(
+        iter_category(&store, "counter", 0),
+        [(1, "counter-b".to_string())]
+    );
+}
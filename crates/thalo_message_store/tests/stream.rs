@@ -0,0 +1,77 @@
+use std::borrow::Cow;
+
+use serde_json::json;
+use thalo::stream_name::StreamName;
+use thalo_message_store::stream::{ExpectedVersion, Stream};
+use thalo_message_store::MessageStore;
+
+fn open_store() -> MessageStore {
+    let db = sled::Config::new().temporary(true).open().unwrap();
+    MessageStore::new(db).unwrap()
+}
+
+/// Writes `count` messages to a new stream.
+fn write_stream(store: &MessageStore, count: u64) -> Stream<'static> {
+    let mut stream = store.stream(StreamName::new("counter-a").unwrap()).unwrap();
+    let messages: Vec<_> = (0..count)
+        .map(|i| ("Incremented", Cow::Owned(json!({ "amount": i }))))
+        .collect();
+    stream
+        .write_messages(&messages, ExpectedVersion::NoStream)
+        .unwrap();
+    stream
+}
+
+/// Returns the positions of the messages in the range.
+fn range(stream: &Stream<'_>, from: u64, to: u64) -> Vec<u64> {
+    stream
+        .iter_messages_range::<()>(from, to)
+        .map(|res| res.unwrap().message().unwrap().position)
+        .collect()
+}
+
+#[test]
+fn iter_messages_range_is_inclusive() {
+    let store = open_store();
+    let stream = write_stream(&store, 5);
+
+    assert_eq!(range(&stream, 0, 4), [0, 1, 2, 3, 4]);
+    assert_eq!(range(&stream, 1, 3), [1, 2, 3]);
+    assert_eq!(range(&stream, 2, 2), [2]);
+}
+
+#[test]
+fn iter_messages_range_past_end() {
+    let store = open_store();
+    let stream = write_stream(&store, 5);
+
+    assert_eq!(range(&stream, 3, 10), [3, 4]);
+    assert!(range(&stream, 5, 10).is_empty());
+}
+
+#[test]
+fn iter_messages_range_to_max() {
+    let store = open_store();
+    let stream = write_stream(&store, 5);
+
+    assert_eq!(range(&stream, 0, u64::MAX), [0, 1, 2, 3, 4]);
+    assert_eq!(range(&stream, 3, u64::MAX), [3, 4]);
+    assert!(range(&stream, u64::MAX, u64::MAX).is_empty());
+}
+
+#[test]
+fn iter_messages_range_from_after_to_is_empty() {
+    let store = open_store();
+    let stream = write_stream(&store, 5);
+
+    assert!(range(&stream, 3, 2).is_empty());
+    assert!(range(&stream, u64::MAX, 0).is_empty());
+}
+
+#[test]
+fn iter_messages_range_of_empty_stream() {
+    let store = open_store();
+    let stream = store.stream(StreamName::new("counter-a").unwrap()).unwrap();
+
+    assert!(range(&stream, 0, u64::MAX).is_empty());
+}
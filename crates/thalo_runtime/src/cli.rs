@@ -1,5 +1,6 @@
 use std::net::SocketAddr;
 use std::path::PathBuf;
+use std::time::Duration;
 
 use anyhow::Result;
 use clap::Parser;
@@ -23,6 +24,9 @@ struct Cli {
     /// Cache size of aggregates (LRU)
     #[clap(long, default_value = "10000")]
     cache_size: u64,
+    /// Maximum time in milliseconds a module may run for when handling a command
+    #[clap(long)]
+    command_timeout: Option<u64>,
     /// Redis relay
     #[clap(long)]
     redis: Option<String>,
@@ -66,7 +70,10 @@ pub async fn start() -> Result<()> {
         }
         None => Relay::Noop,
     };
-    let runtime = Runtime::new(message_store, relay, cli.modules_path, cli.cache_size).await?;
+    let mut runtime = Runtime::new(message_store, relay, cli.modules_path, cli.cache_size).await?;
+    if let Some(command_timeout) = cli.command_timeout {
+        runtime = runtime.with_command_timeout(Duration::from_millis(command_timeout));
+    }
 
     let command_center_server = rpc::server::CommandCenterServer::new(runtime.clone());
     let projection_server = rpc::server::ProjectionServer::new(runtime);
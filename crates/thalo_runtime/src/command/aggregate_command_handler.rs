@@ -16,10 +16,11 @@ use super::outbox_relay::OutboxRelayHandle;
 use super::CommandGatewayHandle;
 use crate::broadcaster::BroadcasterHandle;
 use crate::module::{Event, Module};
+use crate::upcaster::Upcasters;
 
 #[derive(Clone)]
 pub struct AggregateCommandHandlerHandle {
-    sender: mpsc::Sender<ExecuteAggregateCommand>,
+    sender: mpsc::Sender<AggregateCommandHandlerMsg>,
 }
 
 impl AggregateCommandHandlerHandle {
@@ -30,6 +31,7 @@ impl AggregateCommandHandlerHandle {
         message_store: MessageStore,
         broadcaster: BroadcasterHandle,
         cache_size: u64,
+        upcasters: Upcasters,
         module: Module,
     ) -> Self {
         let (sender, receiver) = mpsc::channel(16);
@@ -41,6 +43,7 @@ impl AggregateCommandHandlerHandle {
             message_store,
             broadcaster,
             cache_size,
+            upcasters,
             module,
         ));
 
@@ -55,7 +58,7 @@ impl AggregateCommandHandlerHandle {
         payload: Value,
     ) -> Result<Result<Vec<Message<'static>>, serde_json::Value>> {
         let (reply, recv) = oneshot::channel();
-        let msg = ExecuteAggregateCommand {
+        let msg = AggregateCommandHandlerMsg::Execute {
             name,
             id,
             command,
@@ -66,24 +69,67 @@ impl AggregateCommandHandlerHandle {
         let _ = self.sender.send(msg).await;
         recv.await.context("no response from command handler")?
     }
+
+    pub async fn dry_run(
+        &self,
+        name: Category<'static>,
+        id: ID<'static>,
+        command: String,
+        payload: Value,
+    ) -> Result<Result<Vec<Event<'static>>, serde_json::Value>> {
+        let (reply, recv) = oneshot::channel();
+        let msg = AggregateCommandHandlerMsg::DryRun {
+            name,
+            id,
+            command,
+            payload,
+            reply,
+        };
+
+        let _ = self.sender.send(msg).await;
+        recv.await.context("no response from command handler")?
+    }
+
+    pub async fn delete_stream(&self, name: Category<'static>, id: ID<'static>) -> Result<usize> {
+        let (reply, recv) = oneshot::channel();
+        let msg = AggregateCommandHandlerMsg::DeleteStream { name, id, reply };
+
+        let _ = self.sender.send(msg).await;
+        recv.await.context("no response from command handler")?
+    }
 }
 
-struct ExecuteAggregateCommand {
-    name: Category<'static>,
-    id: ID<'static>,
-    command: String,
-    payload: Value,
-    reply: oneshot::Sender<Result<Result<Vec<Message<'static>>, serde_json::Value>>>,
+enum AggregateCommandHandlerMsg {
+    Execute {
+        name: Category<'static>,
+        id: ID<'static>,
+        command: String,
+        payload: Value,
+        reply: oneshot::Sender<Result<Result<Vec<Message<'static>>, serde_json::Value>>>,
+    },
+    DryRun {
+        name: Category<'static>,
+        id: ID<'static>,
+        command: String,
+        payload: Value,
+        reply: oneshot::Sender<Result<Result<Vec<Event<'static>>, serde_json::Value>>>,
+    },
+    DeleteStream {
+        name: Category<'static>,
+        id: ID<'static>,
+        reply: oneshot::Sender<Result<usize>>,
+    },
 }
 
 async fn run_aggregate_command_handler(
-    mut receiver: mpsc::Receiver<ExecuteAggregateCommand>,
+    mut receiver: mpsc::Receiver<AggregateCommandHandlerMsg>,
     command_gateway: CommandGatewayHandle,
     name: Category<'static>,
     outbox_relay: OutboxRelayHandle,
     message_store: MessageStore,
     broadcaster: BroadcasterHandle,
     cache_size: u64,
+    upcasters: Upcasters,
     module: Module,
 ) -> Result<()> {
     let entity_command_handlers = Cache::new(cache_size);
@@ -92,37 +138,80 @@ async fn run_aggregate_command_handler(
         outbox_relay,
         message_store,
         broadcaster,
+        upcasters,
         module,
         entity_command_handlers,
     };
 
     while let Some(msg) = receiver.recv().await {
-        let res = handler
-            .execute(msg.name, msg.id, msg.command, msg.payload)
-            .await;
-        let res = match res {
-            Ok(res) => Ok(res),
-            Err((err, None)) => Err(err),
-            Err((err, Some(trap))) => {
-                error!("aggregate trapped: {trap}");
-                let _ = msg.reply.send(Err(err));
-                break;
+        let trapped = match msg {
+            AggregateCommandHandlerMsg::Execute {
+                name,
+                id,
+                command,
+                payload,
+                reply,
+            } => {
+                let res = handler.execute(name, id, command, payload).await;
+                reply_or_trap(res, reply)
+            }
+            AggregateCommandHandlerMsg::DryRun {
+                name,
+                id,
+                command,
+                payload,
+                reply,
+            } => {
+                let res = handler.dry_run(name, id, command, payload).await;
+                reply_or_trap(res, reply)
+            }
+            AggregateCommandHandlerMsg::DeleteStream { name, id, reply } => {
+                let res = handler.delete_stream(name, id).await;
+                let _ = reply.send(res);
+                false
             }
         };
+        if trapped {
+            warn!(%name, "aggregate command handler restarting");
 
-        let _ = msg.reply.send(res);
+            let module = handler.module.new_instance().await?;
+            return command_gateway.start_module_from_module(name, module).await;
+        }
     }
 
-    warn!(%name, "aggregate command handler restarting");
+    // The receiver closes when the module is replaced, such as when it's
+    // reloaded, so the handler stops without restarting.
+    Ok(())
+}
 
-    let module = handler.module.new_instance().await?;
-    command_gateway.start_module_from_module(name, module).await
+/// Replies with the result, returning `true` if the aggregate trapped and needs
+/// to be restarted.
+fn reply_or_trap<T>(
+    res: Result<T, (anyhow::Error, Option<Trap>)>,
+    reply: oneshot::Sender<Result<T>>,
+) -> bool {
+    match res {
+        Ok(res) => {
+            let _ = reply.send(Ok(res));
+            false
+        }
+        Err((err, None)) => {
+            let _ = reply.send(Err(err));
+            false
+        }
+        Err((err, Some(trap))) => {
+            error!("aggregate trapped: {trap}");
+            let _ = reply.send(Err(err));
+            true
+        }
+    }
 }
 
 struct AggregateCommandHandler {
     outbox_relay: OutboxRelayHandle,
     message_store: MessageStore,
     broadcaster: BroadcasterHandle,
+    upcasters: Upcasters,
     module: Module,
     entity_command_handlers: Cache<StreamName<'static>, EntityCommandHandlerHandle>,
 }
@@ -136,6 +225,48 @@ impl AggregateCommandHandler {
         payload: Value,
     ) -> Result<Result<Vec<Message<'static>>, serde_json::Value>, (anyhow::Error, Option<Trap>)>
     {
+        self.entity_command_handler(name, id)
+            .await?
+            .execute(command, payload)
+            .await
+            .map_err(|err| {
+                let trap = err.root_cause().downcast_ref().copied();
+                (err, trap)
+            })
+    }
+
+    async fn dry_run(
+        &self,
+        name: Category<'static>,
+        id: ID<'static>,
+        command: String,
+        payload: Value,
+    ) -> Result<Result<Vec<Event<'static>>, serde_json::Value>, (anyhow::Error, Option<Trap>)> {
+        self.entity_command_handler(name, id)
+            .await?
+            .dry_run(command, payload)
+            .await
+            .map_err(|err| {
+                let trap = err.root_cause().downcast_ref().copied();
+                (err, trap)
+            })
+    }
+
+    /// Deletes an entity's stream, evicting its cached command handler first so
+    /// the aggregate is rebuilt from an empty stream on the next command.
+    async fn delete_stream(&self, name: Category<'static>, id: ID<'static>) -> Result<usize> {
+        let stream_name = StreamName::from_parts(name, Some(&id))?;
+        self.entity_command_handlers.invalidate(&stream_name).await;
+
+        let mut stream = self.message_store.stream(stream_name)?;
+        Ok(stream.delete()?)
+    }
+
+    async fn entity_command_handler(
+        &self,
+        name: Category<'static>,
+        id: ID<'static>,
+    ) -> Result<EntityCommandHandlerHandle, (anyhow::Error, Option<Trap>)> {
         let Ok(stream_name) = StreamName::from_parts(name, Some(&id)) else {
             return Err((anyhow!("invalid name or id"), None));
         };
@@ -150,9 +281,14 @@ impl AggregateCommandHandler {
                 for res in stream.iter_all_messages::<()>() {
                     let raw_message = res?;
                     let message = raw_message.message()?;
+                    let (event_type, data) = self.upcasters.upcast(
+                        &stream.stream_name().category(),
+                        message.msg_type.into_owned(),
+                        message.data.into_owned(),
+                    );
                     let event = Event {
-                        event: message.msg_type,
-                        payload: Cow::Owned(serde_json::to_string(&message.data)?),
+                        event: Cow::Owned(event_type),
+                        payload: Cow::Owned(serde_json::to_string(&data)?),
                     };
                     instance.apply(&[(message.position, event)]).await?;
                     trace!(stream_name = ?stream.stream_name(), position = message.position, "applied event");
@@ -172,13 +308,6 @@ impl AggregateCommandHandler {
                 (anyhow!("{err}"), err.root_cause().downcast_ref().copied())
             })?;
 
-        entry
-            .value()
-            .execute(command, payload)
-            .await
-            .map_err(|err| {
-                let trap = err.root_cause().downcast_ref().copied();
-                (err, trap)
-            })
+        Ok(entry.into_value())
     }
 }
@@ -3,7 +3,7 @@ use std::path::PathBuf;
 
 use anyhow::{anyhow, Context, Result};
 use serde_json::Value;
-use thalo::stream_name::{Category, ID};
+use thalo::stream_name::{Category, StreamName, ID};
 use thalo_message_store::message::Message;
 use thalo_message_store::MessageStore;
 use tokio::fs;
@@ -14,8 +14,9 @@ use wasmtime::Engine;
 use super::aggregate_command_handler::AggregateCommandHandlerHandle;
 use super::outbox_relay::OutboxRelayHandle;
 use crate::broadcaster::BroadcasterHandle;
-use crate::module::Module;
+use crate::module::{CommandTimeout, Event, Module};
 use crate::relay::Relay;
+use crate::upcaster::Upcasters;
 
 #[derive(Clone)]
 pub struct CommandGatewayHandle {
@@ -29,6 +30,8 @@ impl CommandGatewayHandle {
         relay: Relay,
         broadcaster: BroadcasterHandle,
         cache_size: u64,
+        upcasters: Upcasters,
+        command_timeout: CommandTimeout,
         modules_path: PathBuf,
     ) -> Self {
         let (sender, receiver) = mpsc::channel(16);
@@ -40,6 +43,8 @@ impl CommandGatewayHandle {
             relay,
             broadcaster,
             cache_size,
+            upcasters,
+            command_timeout,
             modules_path,
         ));
 
@@ -66,6 +71,34 @@ impl CommandGatewayHandle {
         recv.await.context("no response from command handler")?
     }
 
+    pub async fn dry_run(
+        &self,
+        name: Category<'static>,
+        id: ID<'static>,
+        command: String,
+        payload: Value,
+    ) -> Result<Result<Vec<Event<'static>>, serde_json::Value>> {
+        let (reply, recv) = oneshot::channel();
+        let msg = CommandGatewayMsg::DryRun {
+            name,
+            id,
+            command,
+            payload,
+            reply,
+        };
+
+        let _ = self.sender.send(msg).await;
+        recv.await.context("no response from command handler")?
+    }
+
+    pub async fn delete_stream(&self, name: Category<'static>, id: ID<'static>) -> Result<usize> {
+        let (reply, recv) = oneshot::channel();
+        let msg = CommandGatewayMsg::DeleteStream { name, id, reply };
+
+        let _ = self.sender.send(msg).await;
+        recv.await.context("no response from command gateway")?
+    }
+
     pub async fn start_module_from_file(
         &self,
         name: Category<'static>,
@@ -103,6 +136,18 @@ enum CommandGatewayMsg {
         payload: Value,
         reply: oneshot::Sender<Result<Result<Vec<Message<'static>>, serde_json::Value>>>,
     },
+    DryRun {
+        name: Category<'static>,
+        id: ID<'static>,
+        command: String,
+        payload: Value,
+        reply: oneshot::Sender<Result<Result<Vec<Event<'static>>, serde_json::Value>>>,
+    },
+    DeleteStream {
+        name: Category<'static>,
+        id: ID<'static>,
+        reply: oneshot::Sender<Result<usize>>,
+    },
     StartModuleFromFile {
         name: Category<'static>,
         path: PathBuf,
@@ -123,6 +168,8 @@ async fn run_command_gateway(
     relay: Relay,
     broadcaster: BroadcasterHandle,
     cache_size: u64,
+    upcasters: Upcasters,
+    command_timeout: CommandTimeout,
     modules_path: PathBuf,
 ) {
     let mut cmd_gateway = CommandGateway {
@@ -132,6 +179,8 @@ async fn run_command_gateway(
         relay,
         broadcaster,
         cache_size,
+        upcasters,
+        command_timeout,
         modules: HashMap::new(),
     };
 
@@ -154,6 +203,20 @@ async fn run_command_gateway(
                 let res = cmd_gateway.execute(name, id, command, payload).await;
                 let _ = reply.send(res);
             }
+            CommandGatewayMsg::DryRun {
+                name,
+                id,
+                command,
+                payload,
+                reply,
+            } => {
+                let res = cmd_gateway.dry_run(name, id, command, payload).await;
+                let _ = reply.send(res);
+            }
+            CommandGatewayMsg::DeleteStream { name, id, reply } => {
+                let res = cmd_gateway.delete_stream(name, id).await;
+                let _ = reply.send(res);
+            }
             CommandGatewayMsg::StartModuleFromFile { name, path, reply } => {
                 let res = cmd_gateway.start_module_from_file(name, path).await;
                 let _ = reply.send(res);
@@ -179,6 +242,8 @@ struct CommandGateway {
     relay: Relay,
     broadcaster: BroadcasterHandle,
     cache_size: u64,
+    upcasters: Upcasters,
+    command_timeout: CommandTimeout,
     modules: HashMap<Category<'static>, AggregateCommandHandlerHandle>,
 }
 
@@ -201,6 +266,35 @@ impl CommandGateway {
             .await
     }
 
+    async fn dry_run(
+        &mut self,
+        name: Category<'static>,
+        id: ID<'static>,
+        command: String,
+        payload: Value,
+    ) -> Result<Result<Vec<Event<'static>>, serde_json::Value>> {
+        let Some(aggregate_command_handler) = self.modules.get(&name).cloned() else {
+            return Err(anyhow!(
+                "aggregate '{name}' does not exist or is not running"
+            ));
+        };
+
+        aggregate_command_handler
+            .dry_run(name, id, command, payload)
+            .await
+    }
+
+    async fn delete_stream(&mut self, name: Category<'static>, id: ID<'static>) -> Result<usize> {
+        if let Some(aggregate_command_handler) = self.modules.get(&name).cloned() {
+            return aggregate_command_handler.delete_stream(name, id).await;
+        }
+
+        // Without a running module, there are no cached handlers to evict.
+        let stream_name = StreamName::from_parts(name, Some(&id))?;
+        let mut stream = self.message_store.stream(stream_name)?;
+        Ok(stream.delete()?)
+    }
+
     async fn start_module(&mut self, name: Category<'static>, module: Module) -> Result<()> {
         let outbox = self.message_store.outbox(name.clone())?;
         let outbox_relay = OutboxRelayHandle::new(name.clone(), outbox, self.relay.clone());
@@ -212,6 +306,7 @@ impl CommandGateway {
             self.message_store.clone(),
             self.broadcaster.clone(),
             self.cache_size,
+            self.upcasters.clone(),
             module,
         );
 
@@ -225,7 +320,8 @@ impl CommandGateway {
         name: Category<'static>,
         path: PathBuf,
     ) -> Result<()> {
-        let module = Module::from_file(self.engine.clone(), path).await?;
+        let module =
+            Module::from_file(self.engine.clone(), path, self.command_timeout.clone()).await?;
         self.start_module(name, module).await
     }
 
@@ -3,7 +3,7 @@ use std::borrow::Cow;
 use anyhow::{Context as AnyhowContext, Result};
 use serde_json::Value;
 use thalo_message_store::message::Message;
-use thalo_message_store::stream::Stream;
+use thalo_message_store::stream::{ExpectedVersion, Stream};
 use tokio::sync::{mpsc, oneshot};
 use tracing::{error, trace};
 
@@ -13,14 +13,21 @@ use crate::module::{Event, ModuleInstance};
 
 #[derive(Clone)]
 pub struct EntityCommandHandlerHandle {
-    sender: mpsc::Sender<ExecuteEntityCommand>,
+    sender: mpsc::Sender<EntityCommandHandlerMsg>,
 }
 
 #[derive(Debug)]
-struct ExecuteEntityCommand {
-    command: String,
-    payload: Value,
-    reply: oneshot::Sender<Result<Result<Vec<Message<'static>>, serde_json::Value>>>,
+enum EntityCommandHandlerMsg {
+    Execute {
+        command: String,
+        payload: Value,
+        reply: oneshot::Sender<Result<Result<Vec<Message<'static>>, serde_json::Value>>>,
+    },
+    DryRun {
+        command: String,
+        payload: Value,
+        reply: oneshot::Sender<Result<Result<Vec<Event<'static>>, serde_json::Value>>>,
+    },
 }
 
 impl EntityCommandHandlerHandle {
@@ -48,7 +55,24 @@ impl EntityCommandHandlerHandle {
         payload: Value,
     ) -> Result<Result<Vec<Message<'static>>, serde_json::Value>> {
         let (reply, recv) = oneshot::channel();
-        let msg = ExecuteEntityCommand {
+        let msg = EntityCommandHandlerMsg::Execute {
+            command,
+            payload,
+            reply,
+        };
+
+        let _ = self.sender.send(msg).await;
+        recv.await
+            .context("no response from entity command handler")?
+    }
+
+    pub async fn dry_run(
+        &self,
+        command: String,
+        payload: Value,
+    ) -> Result<Result<Vec<Event<'static>>, serde_json::Value>> {
+        let (reply, recv) = oneshot::channel();
+        let msg = EntityCommandHandlerMsg::DryRun {
             command,
             payload,
             reply,
@@ -61,7 +85,7 @@ impl EntityCommandHandlerHandle {
 }
 
 async fn run_entity_command_handler(
-    mut receiver: mpsc::Receiver<ExecuteEntityCommand>,
+    mut receiver: mpsc::Receiver<EntityCommandHandlerMsg>,
     outbox_relay: OutboxRelayHandle,
     broadcaster: BroadcasterHandle,
     instance: ModuleInstance,
@@ -75,8 +99,24 @@ async fn run_entity_command_handler(
     };
 
     while let Some(msg) = receiver.recv().await {
-        let res = handler.execute(msg.command, msg.payload).await;
-        let _ = msg.reply.send(res);
+        match msg {
+            EntityCommandHandlerMsg::Execute {
+                command,
+                payload,
+                reply,
+            } => {
+                let res = handler.execute(command, payload).await;
+                let _ = reply.send(res);
+            }
+            EntityCommandHandlerMsg::DryRun {
+                command,
+                payload,
+                reply,
+            } => {
+                let res = handler.dry_run(command, payload).await;
+                let _ = reply.send(res);
+            }
+        }
     }
 
     trace!(stream_name = %handler.stream.stream_name(), "stopping entity command handler");
@@ -134,7 +174,9 @@ impl EntityCommandHandler {
                 Ok((event.event.as_ref(), Cow::Owned(payload)))
             })
             .collect::<anyhow::Result<_>>()?;
-        let written_messages = self.stream.write_messages(&messages, sequence)?;
+        let written_messages = self
+            .stream
+            .write_messages(&messages, ExpectedVersion::from(sequence))?;
 
         for message in &written_messages {
             if let Err(err) = self
@@ -156,4 +198,14 @@ impl EntityCommandHandler {
             .collect();
         Ok(Ok(reply_messages))
     }
+
+    /// Handles a command without applying or persisting the resulting events.
+    async fn dry_run(
+        &self,
+        command: String,
+        payload: Value,
+    ) -> Result<Result<Vec<Event<'static>>, serde_json::Value>> {
+        let payload = serde_json::to_string(&payload)?;
+        self.instance.handle(&command, &payload).await
+    }
 }
@@ -5,7 +5,11 @@ mod projection;
 pub mod relay;
 pub mod rpc;
 mod runtime;
+mod upcaster;
 
-pub use projection::Projection;
+pub use projection::{
+    DeadLetterProjection, EventTypeStats, InstrumentedProjection, Projection, ProjectionProgress,
+};
 pub use runtime::Runtime;
 pub use thalo_message_store::message::Message;
+pub use upcaster::Upcaster;
@@ -3,8 +3,11 @@ pub mod wit_aggregate;
 use std::borrow::Cow;
 use std::ops::DerefMut;
 use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
 use std::sync::Arc;
-use std::{fmt, str};
+use std::time::Duration;
+use std::{fmt, str, thread};
 
 use anyhow::{anyhow, bail, Context as AnyhowContext, Result};
 use async_trait::async_trait;
@@ -13,12 +16,23 @@ use tokio::sync::Mutex;
 use tracing::{info, trace};
 use tracing_tunnel::TracingEventReceiver;
 use wasmtime::component::{Component, InstancePre, Linker, ResourceAny};
-use wasmtime::{Engine, Store};
+use wasmtime::{Engine, Store, Trap};
 use wasmtime_wasi::preview2::{command, Stdout, Table, WasiCtx, WasiCtxBuilder, WasiView};
 
 use self::wit_aggregate::Aggregate;
 use crate::module::wit_aggregate::{tracing as wit_tracing, AggregateError};
 
+/// Interval at which the engine's epoch is incremented.
+///
+/// Command timeouts are measured in epochs, so this is the precision at which
+/// they are enforced.
+pub const EPOCH_TICK: Duration = Duration::from_millis(10);
+
+/// Epoch deadline used when there is no command timeout, which is never
+/// reached but leaves room for the engine's epoch to be added without
+/// overflowing.
+const NO_DEADLINE: u64 = u64::MAX / 2;
+
 #[derive(Clone)]
 pub struct Module {
     // TODO: This Arc shouldn't be necessary, but `wasmtime::component::bindgen` doesn't generate
@@ -28,6 +42,7 @@ pub struct Module {
     store: Arc<Mutex<Store<CommandCtx>>>,
     component: Component,
     instance_pre: InstancePre<CommandCtx>,
+    timeout: CommandTimeout,
 }
 
 #[derive(Clone)]
@@ -36,6 +51,69 @@ pub struct ModuleInstance {
     store: Arc<Mutex<Store<CommandCtx>>>,
     resource: ResourceAny,
     sequence: Option<u64>,
+    timeout: CommandTimeout,
+}
+
+/// The maximum duration a single call into a module may run for, shared by
+/// all modules of a runtime.
+///
+/// Calls exceeding the timeout are interrupted with a trap, causing the
+/// aggregate to be restarted.
+#[derive(Clone, Debug, Default)]
+pub struct CommandTimeout(Arc<AtomicU64>);
+
+impl CommandTimeout {
+    /// Sets the timeout, rounded up to the nearest [`EPOCH_TICK`].
+    pub fn set(&self, timeout: Option<Duration>) {
+        let ticks = timeout
+            .map(|timeout| {
+                let ticks = timeout.as_nanos().div_ceil(EPOCH_TICK.as_nanos());
+                u64::try_from(ticks).unwrap_or(u64::MAX).max(1)
+            })
+            .unwrap_or(0);
+        self.0.store(ticks, Ordering::Relaxed);
+    }
+
+    /// Sets the store's epoch deadline before calling into a module.
+    fn set_deadline(&self, store: &mut Store<CommandCtx>) {
+        let ticks = match self.0.load(Ordering::Relaxed) {
+            0 => NO_DEADLINE,
+            ticks => ticks,
+        };
+        store.set_epoch_deadline(ticks);
+    }
+}
+
+/// Advances an engine's epoch every [`EPOCH_TICK`] on a dedicated thread, so
+/// command timeouts are enforced even if a module is blocking an async worker
+/// thread.
+///
+/// The thread stops once the ticker is dropped.
+#[derive(Debug)]
+pub struct EpochTicker {
+    _stop: mpsc::Sender<()>,
+}
+
+impl EpochTicker {
+    pub fn start(engine: Engine) -> Self {
+        let (stop, stopped) = mpsc::channel();
+        thread::spawn(move || {
+            while let Err(RecvTimeoutError::Timeout) = stopped.recv_timeout(EPOCH_TICK) {
+                engine.increment_epoch();
+            }
+        });
+
+        EpochTicker { _stop: stop }
+    }
+}
+
+/// Adds context to errors caused by a call exceeding the command timeout.
+fn timeout_context(err: anyhow::Error) -> anyhow::Error {
+    if matches!(err.downcast_ref(), Some(Trap::Interrupt)) {
+        err.context("command timed out")
+    } else {
+        err
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
@@ -82,16 +160,23 @@ impl wit_tracing::Host for TracingSubscriber {
 }
 
 impl Module {
-    pub async fn new(engine: Engine, component: Component) -> Result<Self> {
+    pub async fn new(
+        engine: Engine,
+        component: Component,
+        timeout: CommandTimeout,
+    ) -> Result<Self> {
         let ctx = CommandCtx::default();
         let mut store = Store::new(&engine, ctx);
+        timeout.set_deadline(&mut store);
         let mut linker: Linker<CommandCtx> = Linker::new(&engine);
         command::add_to_linker(&mut linker)?;
         wit_tracing::add_to_linker(&mut linker, |ctx| &mut ctx.tracing_subscriber)?;
 
         let instance_pre = linker.instantiate_pre(&component)?;
         let (aggregate, _instance) =
-            wit_aggregate::Aggregate::instantiate_pre(&mut store, &instance_pre).await?;
+            wit_aggregate::Aggregate::instantiate_pre(&mut store, &instance_pre)
+                .await
+                .map_err(timeout_context)?;
 
         Ok(Module {
             aggregate: Arc::new(aggregate),
@@ -99,15 +184,16 @@ impl Module {
             store: Arc::new(Mutex::new(store)),
             component,
             instance_pre,
+            timeout,
         })
     }
 
-    pub async fn from_file<T>(engine: Engine, file: T) -> Result<Self>
+    pub async fn from_file<T>(engine: Engine, file: T, timeout: CommandTimeout) -> Result<Self>
     where
         T: AsRef<Path> + fmt::Debug,
     {
         let component = Component::from_file(&engine, &file)?;
-        let module = Module::new(engine, component).await?;
+        let module = Module::new(engine, component, timeout).await?;
 
         info!(?file, "loaded module from file");
 
@@ -117,8 +203,11 @@ impl Module {
     pub async fn new_instance(self) -> Result<Self> {
         let ctx = CommandCtx::default();
         let mut store = Store::new(&self.engine, ctx);
+        self.timeout.set_deadline(&mut store);
         let (aggregate, _instance) =
-            wit_aggregate::Aggregate::instantiate_pre(&mut store, &self.instance_pre).await?;
+            wit_aggregate::Aggregate::instantiate_pre(&mut store, &self.instance_pre)
+                .await
+                .map_err(timeout_context)?;
 
         Ok(Module {
             aggregate: Arc::new(aggregate),
@@ -126,17 +215,20 @@ impl Module {
             store: Arc::new(Mutex::new(store)),
             component: self.component,
             instance_pre: self.instance_pre,
+            timeout: self.timeout,
         })
     }
 
     pub async fn init(&self, id: &str) -> Result<ModuleInstance> {
         let resource = {
             let mut store = self.store.lock().await;
+            self.timeout.set_deadline(&mut store);
             self.aggregate
                 .aggregate()
                 .entity()
                 .call_constructor(store.deref_mut(), id)
-                .await?
+                .await
+                .map_err(timeout_context)?
         };
 
         trace!(%id, "initialized module");
@@ -145,6 +237,7 @@ impl Module {
             Arc::clone(&self.store),
             Arc::clone(&self.aggregate),
             resource,
+            self.timeout.clone(),
         ))
     }
 }
@@ -154,12 +247,14 @@ impl ModuleInstance {
         store: Arc<Mutex<Store<CommandCtx>>>,
         aggregate: Arc<Aggregate>,
         resource: ResourceAny,
+        timeout: CommandTimeout,
     ) -> Self {
         ModuleInstance {
             aggregate,
             store,
             resource,
             sequence: None,
+            timeout,
         }
     }
 
@@ -200,6 +295,7 @@ impl ModuleInstance {
             .collect::<Result<_>>()?;
 
         let mut store = self.store.lock().await;
+        self.timeout.set_deadline(&mut store);
         let res = self
             .aggregate
             .aggregate()
@@ -210,7 +306,7 @@ impl ModuleInstance {
                 res.map_err(AggregateError::from)
                     .map_err(anyhow::Error::from)
             })
-            .map_err(anyhow::Error::from);
+            .map_err(timeout_context);
         if let Err(err) | Ok(Err(err)) = res {
             self.sequence = original_sequence;
             return Err(err);
@@ -230,11 +326,13 @@ impl ModuleInstance {
 
         let result = {
             let mut store = self.store.lock().await;
+            self.timeout.set_deadline(&mut store);
             self.aggregate
                 .aggregate()
                 .entity()
                 .call_handle(store.deref_mut(), self.resource, command)
-                .await?
+                .await
+                .map_err(timeout_context)?
                 .map_err(AggregateError::from)
         };
         match result {
@@ -253,7 +351,11 @@ impl ModuleInstance {
 
     pub async fn resource_drop(&self) -> Result<()> {
         let mut store = self.store.lock().await;
-        self.resource.resource_drop_async(store.deref_mut()).await?;
+        self.timeout.set_deadline(&mut store);
+        self.resource
+            .resource_drop_async(store.deref_mut())
+            .await
+            .map_err(timeout_context)?;
         Ok(())
     }
 }
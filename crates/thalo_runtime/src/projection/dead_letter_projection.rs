@@ -0,0 +1,101 @@
+use std::marker::PhantomData;
+
+use async_trait::async_trait;
+use thalo_message_store::message::Message;
+use tracing::{error, warn};
+
+use super::Projection;
+
+/// Wraps a [`Projection`], retrying events which fail to be handled and
+/// passing them to a dead letter handler once the attempts are exhausted.
+///
+/// Dead lettered events are treated as handled, so the projection is
+/// acknowledged past them and continues making progress rather than being
+/// blocked forever by a single unprocessable event.
+pub struct DeadLetterProjection<P, F> {
+    inner: P,
+    max_attempts: usize,
+    on_dead_letter: F,
+}
+
+impl<P, F> DeadLetterProjection<P, F>
+where
+    P: Projection,
+    F: FnMut(&Message<'static, P::Event>, &anyhow::Error),
+{
+    /// Creates a new dead letter projection, handling each event at most
+    /// `max_attempts` times before calling `on_dead_letter`.
+    ///
+    /// An event is always attempted at least once.
+    pub fn new(inner: P, max_attempts: usize, on_dead_letter: F) -> Self {
+        DeadLetterProjection {
+            inner,
+            max_attempts: max_attempts.max(1),
+            on_dead_letter,
+        }
+    }
+
+    pub fn inner(&self) -> &P {
+        &self.inner
+    }
+
+    pub fn into_inner(self) -> P {
+        self.inner
+    }
+}
+
+#[async_trait]
+impl<P, F> Projection for DeadLetterProjection<P, F>
+where
+    P: Projection + Send + Sync,
+    P::Event: Send + Sync,
+    F: FnMut(&Message<'static, P::Event>, &anyhow::Error) + Send + Sync,
+{
+    type Event = P::Event;
+
+    async fn handle(&mut self, message: Message<'static, Self::Event>) -> anyhow::Result<()> {
+        let mut attempt = 1;
+        loop {
+            let err = match self.inner.handle(copy_message(&message)).await {
+                Ok(()) => return Ok(()),
+                Err(err) => err,
+            };
+
+            if attempt < self.max_attempts {
+                warn!(
+                    global_id = message.global_id,
+                    attempt, "failed to handle event, retrying: {err}"
+                );
+                attempt += 1;
+                continue;
+            }
+
+            error!(
+                global_id = message.global_id,
+                stream_name = %message.stream_name,
+                msg_type = %message.msg_type,
+                "moving event to dead letter after {attempt} attempts: {err}"
+            );
+            (self.on_dead_letter)(&message, &err);
+            return Ok(());
+        }
+    }
+
+    async fn last_global_id(&self) -> anyhow::Result<Option<u64>> {
+        self.inner.last_global_id().await
+    }
+}
+
+/// Copies a message without requiring the event type to implement `Clone`.
+fn copy_message<T>(message: &Message<'static, T>) -> Message<'static, T> {
+    Message {
+        id: message.id,
+        global_id: message.global_id,
+        position: message.position,
+        stream_name: message.stream_name.clone(),
+        msg_type: message.msg_type.clone(),
+        data: message.data.clone(),
+        time: message.time,
+        _marker: PhantomData,
+    }
+}
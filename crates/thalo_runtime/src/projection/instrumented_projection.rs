@@ -0,0 +1,111 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use thalo_message_store::message::Message;
+use tracing::debug;
+
+use super::Projection;
+
+/// Wraps a [`Projection`], recording how many events were handled and how long
+/// they took, grouped by event type.
+///
+/// This can wrap any projection without modifying it, and is useful for
+/// finding which event types dominate processing time.
+pub struct InstrumentedProjection<P> {
+    inner: P,
+    stats: HashMap<String, EventTypeStats>,
+}
+
+/// Processing statistics for a single event type.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct EventTypeStats {
+    /// Number of events handled successfully.
+    pub handled: u64,
+    /// Number of events which returned an error.
+    pub failed: u64,
+    /// Total time spent handling events.
+    pub total_duration: Duration,
+    /// Longest time spent handling a single event.
+    pub max_duration: Duration,
+}
+
+impl<P> InstrumentedProjection<P> {
+    pub fn new(inner: P) -> Self {
+        InstrumentedProjection {
+            inner,
+            stats: HashMap::new(),
+        }
+    }
+
+    /// Returns statistics keyed by event type.
+    pub fn stats(&self) -> &HashMap<String, EventTypeStats> {
+        &self.stats
+    }
+
+    pub fn inner(&self) -> &P {
+        &self.inner
+    }
+
+    pub fn into_inner(self) -> P {
+        self.inner
+    }
+}
+
+impl EventTypeStats {
+    /// Average time spent handling an event.
+    pub fn average_duration(&self) -> Duration {
+        let count = self.handled + self.failed;
+        if count == 0 {
+            return Duration::ZERO;
+        }
+
+        // Dividing the nanoseconds avoids truncating the count to a u32.
+        let nanos = self.total_duration.as_nanos() / u128::from(count);
+        Duration::from_nanos(nanos as u64)
+    }
+
+    fn record(&mut self, duration: Duration, success: bool) {
+        if success {
+            self.handled += 1;
+        } else {
+            self.failed += 1;
+        }
+        self.total_duration += duration;
+        self.max_duration = self.max_duration.max(duration);
+    }
+}
+
+#[async_trait]
+impl<P> Projection for InstrumentedProjection<P>
+where
+    P: Projection + Send + Sync,
+    P::Event: Send,
+{
+    type Event = P::Event;
+
+    async fn handle(&mut self, message: Message<'static, Self::Event>) -> anyhow::Result<()> {
+        let event_type = message.msg_type.clone().into_owned();
+
+        let start = Instant::now();
+        let res = self.inner.handle(message).await;
+        let elapsed = start.elapsed();
+
+        debug!(
+            %event_type,
+            elapsed_us = elapsed.as_micros() as u64,
+            success = res.is_ok(),
+            "handled event"
+        );
+        self.stats
+            .entry(event_type)
+            .or_default()
+            .record(elapsed, res.is_ok());
+
+        res
+    }
+
+    async fn last_global_id(&self) -> anyhow::Result<Option<u64>> {
+        self.inner.last_global_id().await
+    }
+}
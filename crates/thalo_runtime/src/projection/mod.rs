@@ -1,7 +1,11 @@
+mod dead_letter_projection;
+mod instrumented_projection;
 mod projection_gateway;
 mod projection_subscription;
 
 use async_trait::async_trait;
+pub use dead_letter_projection::*;
+pub use instrumented_projection::*;
 pub use projection_gateway::*;
 use thalo_message_store::message::Message;
 
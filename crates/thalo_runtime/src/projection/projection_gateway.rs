@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::time::Duration;
 
 use anyhow::{Context, Result};
@@ -63,6 +63,31 @@ impl ProjectionGatewayHandle {
         recv.await.context("no response from projection gateway")?
     }
 
+    pub async fn reset_projection(&self, name: String) -> Result<()> {
+        let (reply, recv) = oneshot::channel();
+        let msg = ProjectionGatewayMsg::ResetProjection { name, reply };
+        let _ = self.sender.send(msg).await;
+        recv.await.context("no response from projection gateway")?
+    }
+
+    pub async fn set_position(&self, name: String, global_id: u64) -> Result<()> {
+        let (reply, recv) = oneshot::channel();
+        let msg = ProjectionGatewayMsg::SetPosition {
+            name,
+            global_id,
+            reply,
+        };
+        let _ = self.sender.send(msg).await;
+        recv.await.context("no response from projection gateway")?
+    }
+
+    pub async fn progress(&self, name: String) -> Result<ProjectionProgress> {
+        let (reply, recv) = oneshot::channel();
+        let msg = ProjectionGatewayMsg::Progress { name, reply };
+        let _ = self.sender.send(msg).await;
+        recv.await.context("no response from projection gateway")?
+    }
+
     pub(crate) async fn set_subscription_to_process_new_events(&self, name: String) -> Result<()> {
         let (reply, recv) = oneshot::channel();
         let msg = ProjectionGatewayMsg::SetProjectionToProcessNewEvents { name, reply };
@@ -71,6 +96,23 @@ impl ProjectionGatewayHandle {
     }
 }
 
+/// A projection's position in the global event log, compared to the last
+/// event in the log.
+///
+/// Events which aren't relevant to a projection are only acknowledged once it
+/// receives live events, so the position may stay behind the head until then.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ProjectionProgress {
+    /// Global id of the last event acknowledged by the projection.
+    pub position: Option<u64>,
+    /// Global id of the last event in the global event log.
+    pub head: Option<u64>,
+    /// Whether every historical event has been sent to the projection, and it
+    /// now receives live events. This is `false` while the projection is
+    /// stopped or still replaying events.
+    pub live: bool,
+}
+
 #[derive(Clone, Debug)]
 pub struct EventInterest<'a> {
     pub category: CategoryInterest<'a>,
@@ -110,6 +152,19 @@ enum ProjectionGatewayMsg {
     StopProjection {
         name: String,
     },
+    ResetProjection {
+        name: String,
+        reply: oneshot::Sender<Result<()>>,
+    },
+    SetPosition {
+        name: String,
+        global_id: u64,
+        reply: oneshot::Sender<Result<()>>,
+    },
+    Progress {
+        name: String,
+        reply: oneshot::Sender<Result<ProjectionProgress>>,
+    },
     SetProjectionToProcessNewEvents {
         name: String,
         reply: oneshot::Sender<()>,
@@ -127,6 +182,7 @@ async fn run_projection_gateway(
     let mut projection_gateway = ProjectionGateway {
         sender,
         projections: HashMap::new(),
+        reset_projections: HashSet::new(),
         message_store,
         is_dirty: false,
     };
@@ -148,6 +204,18 @@ async fn run_projection_gateway(
                     ProjectionGatewayMsg::StopProjection { name } => {
                         projection_gateway.stop_projection(name);
                     }
+                    ProjectionGatewayMsg::ResetProjection { name, reply } => {
+                        let res = projection_gateway.reset_projection(name);
+                        let _ = reply.send(res);
+                    }
+                    ProjectionGatewayMsg::SetPosition { name, global_id, reply } => {
+                        let res = projection_gateway.set_position(name, global_id);
+                        let _ = reply.send(res);
+                    }
+                    ProjectionGatewayMsg::Progress { name, reply } => {
+                        let res = projection_gateway.progress(name);
+                        let _ = reply.send(res);
+                    }
                     ProjectionGatewayMsg::SetProjectionToProcessNewEvents { name, reply } => {
                         let res = projection_gateway.set_projection_to_process_new_events(name);
                         let _ = reply.send(res);
@@ -184,6 +252,9 @@ async fn run_projection_gateway(
 struct ProjectionGateway {
     sender: mpsc::Sender<ProjectionGatewayMsg>,
     projections: HashMap<String, Subscription>,
+    /// Projections which have been reset or moved, and haven't resubscribed
+    /// yet.
+    reset_projections: HashSet<String>,
     message_store: MessageStore,
     is_dirty: bool,
 }
@@ -202,6 +273,10 @@ impl ProjectionGateway {
                     let _ = sender.try_send(ProjectionGatewayMsg::StopProjection { name });
                 }
             });
+        } else if self.reset_projections.contains(&name) {
+            // Acknowledgements for events sent before the reset would overwrite
+            // the position it was reset to.
+            warn!(%name, global_id, "ignoring acknowledgement for reset projection");
         } else {
             let mut projection = self.message_store.projection(&name)?;
             projection.acknowledge_event(global_id, true)?;
@@ -235,6 +310,7 @@ impl ProjectionGateway {
             events,
             process_new_events: false,
         };
+        self.reset_projections.remove(&name);
         self.projections.insert(name, subscription);
 
         Ok(())
@@ -291,6 +367,55 @@ impl ProjectionGateway {
         self.projections.remove(&name);
     }
 
+    fn reset_projection(&mut self, name: String) -> Result<()> {
+        // Stopping the subscription closes the projection's event stream, so it
+        // resubscribes from the beginning.
+        match self.projections.remove(&name) {
+            Some(mut subscription) => subscription.projection.reset_position()?,
+            None => self.message_store.projection(&name)?.reset_position()?,
+        }
+        self.reset_projections.insert(name);
+        self.is_dirty = true;
+
+        Ok(())
+    }
+
+    fn set_position(&mut self, name: String, global_id: u64) -> Result<()> {
+        // As with resets, the subscription is stopped so it resubscribes from
+        // the new position.
+        match self.projections.remove(&name) {
+            Some(mut subscription) => subscription.projection.set_position(global_id)?,
+            None => self
+                .message_store
+                .projection(&name)?
+                .set_position(global_id)?,
+        }
+        self.reset_projections.insert(name);
+        self.is_dirty = true;
+
+        Ok(())
+    }
+
+    fn progress(&self, name: String) -> Result<ProjectionProgress> {
+        let (position, live) = match self.projections.get(&name) {
+            Some(subscription) => (
+                subscription.projection.last_seen_event_id(),
+                subscription.process_new_events,
+            ),
+            None => (
+                self.message_store.projection(name)?.last_seen_event_id(),
+                false,
+            ),
+        };
+        let head = self.message_store.global_event_log()?.last_position()?;
+
+        Ok(ProjectionProgress {
+            position,
+            head,
+            live,
+        })
+    }
+
     fn set_projection_to_process_new_events(&mut self, name: String) {
         if let Some(subscription) = self.projections.get_mut(&name) {
             subscription.process_new_events = true;
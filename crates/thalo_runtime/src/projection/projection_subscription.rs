@@ -1,4 +1,3 @@
-use std::iter::Skip;
 use std::time::Duration;
 
 use anyhow::{bail, Context, Result};
@@ -79,9 +78,9 @@ async fn run_projection_subscription(
     last_acknowledged_id: Option<u64>,
     global_event_log: GlobalEventLog,
 ) -> Result<()> {
-    let iter = global_event_log.iter_all_messages().skip(
+    let iter = global_event_log.iter_messages_from(
         last_acknowledged_id
-            .map(|global_id| (global_id as usize) + 1)
+            .map(|global_id| global_id + 1)
             .unwrap_or(0),
     );
 
@@ -138,7 +137,7 @@ struct ProjectionSubscription {
     last_processed_id: Option<u64>,
     pending_events: Vec<Message<'static>>,
     state: ProjectionSubscriptionState,
-    iter: Skip<GlobalEventLogIter>,
+    iter: GlobalEventLogIter,
 }
 
 impl ProjectionSubscription {
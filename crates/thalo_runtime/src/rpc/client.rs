@@ -59,6 +59,12 @@ pub trait CommandCenterClientExt {
     }
 
     async fn publish(&mut self, name: Category<'static>, module: Vec<u8>) -> Result<(), Status>;
+
+    async fn current_version(
+        &mut self,
+        name: Category<'static>,
+        id: ID<'static>,
+    ) -> Result<Option<u64>, Status>;
 }
 
 #[async_trait]
@@ -115,6 +121,21 @@ where
             Err(Status::internal(resp.message))
         }
     }
+
+    async fn current_version(
+        &mut self,
+        name: Category<'static>,
+        id: ID<'static>,
+    ) -> Result<Option<u64>, Status> {
+        let req = Request::new(proto::CurrentVersionRequest {
+            name: name.into_string(),
+            id: id.into_string(),
+        });
+        let resp = CommandCenterClient::current_version(self, req)
+            .await?
+            .into_inner();
+        Ok(resp.version)
+    }
 }
 
 #[async_trait]
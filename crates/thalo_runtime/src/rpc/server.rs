@@ -1,7 +1,10 @@
+use std::collections::HashMap;
 use std::pin::Pin;
 
+use futures::future::join_all;
 use futures::StreamExt as _;
 use thalo::stream_name::{Category, ID};
+use thalo_message_store::error::{Error, ErrorCode};
 use thalo_message_store::message::Message;
 use tokio::sync::mpsc;
 use tokio_stream::wrappers::ReceiverStream;
@@ -13,45 +16,73 @@ pub use super::proto::command_center_server::*;
 pub use super::proto::projection_server::*;
 use crate::Runtime;
 
+/// Maximum number of commands in a single `ExecuteBatch` request.
+pub const MAX_BATCH_SIZE: usize = 100;
+
 #[tonic::async_trait]
 impl proto::command_center_server::CommandCenter for Runtime {
     async fn execute(
         &self,
         request: Request<proto::ExecuteCommand>,
     ) -> Result<Response<proto::ExecuteResponse>, Status> {
-        let proto::ExecuteCommand {
-            name,
-            id,
-            command,
-            payload,
-        } = request.into_inner();
-        let name = Category::new(name).map_err(|_| Status::invalid_argument("invalid name"))?;
-        let id = ID::new(id).map_err(|_| Status::invalid_argument("invalid id"))?;
-        let payload = serde_json::from_str(&payload)
-            .map_err(|err| Status::invalid_argument(format!("invalid payload: {err}")))?;
-
-        let resp = match self.execute(name, id, command, payload).await {
-            Ok(Ok(events)) => proto::ExecuteResponse {
-                success: true,
-                events: events
-                    .into_iter()
-                    .map(proto::Message::try_from)
-                    .collect::<Result<_, _>>()
-                    .map_err(|err| Status::internal(err.to_string()))?,
-                message: "ok".to_string(),
-            },
-            Ok(Err(err)) => proto::ExecuteResponse {
-                success: false,
-                events: vec![],
-                message: serde_json::to_string(&err)
-                    .map_err(|err| Status::internal(format!("failed to serialize error: {err}")))?,
-            },
-            Err(err) => return Err(Status::internal(err.to_string())),
-        };
-
+        let resp = execute_command(self, request.into_inner()).await?;
         Ok(Response::new(resp))
     }
 
+    /// Executes a batch of commands, reporting the result of each command
+    /// individually.
+    ///
+    /// Commands targeting the same stream are executed in the order they were
+    /// given. The command gateway handles one command at a time, so batches
+    /// save round trips rather than executing commands concurrently.
+    ///
+    /// Batches containing more than [`MAX_BATCH_SIZE`] commands are rejected.
+    async fn execute_batch(
+        &self,
+        request: Request<proto::ExecuteBatchRequest>,
+    ) -> Result<Response<proto::ExecuteBatchResponse>, Status> {
+        let proto::ExecuteBatchRequest { commands } = request.into_inner();
+        if commands.len() > MAX_BATCH_SIZE {
+            return Err(Status::invalid_argument(format!(
+                "batch contains {} commands, but the maximum is {MAX_BATCH_SIZE}",
+                commands.len()
+            )));
+        }
+
+        let mut streams: HashMap<(String, String), Vec<(usize, proto::ExecuteCommand)>> =
+            HashMap::new();
+        let len = commands.len();
+        for (i, command) in commands.into_iter().enumerate() {
+            streams
+                .entry((command.name.clone(), command.id.clone()))
+                .or_default()
+                .push((i, command));
+        }
+
+        let stream_results = join_all(streams.into_values().map(|commands| async move {
+            let mut results = Vec::with_capacity(commands.len());
+            for (i, command) in commands {
+                let result = match execute_command(self, command).await {
+                    Ok(resp) => proto::execute_result::Result::Response(resp),
+                    Err(status) => proto::execute_result::Result::Error(proto::ExecuteError {
+                        code: status.code().into(),
+                        message: status.message().to_string(),
+                    }),
+                };
+                results.push((i, result));
+            }
+            results
+        }))
+        .await;
+
+        let mut results = vec![proto::ExecuteResult::default(); len];
+        for (i, result) in stream_results.into_iter().flatten() {
+            results[i].result = Some(result);
+        }
+
+        Ok(Response::new(proto::ExecuteBatchResponse { results }))
+    }
+
     async fn publish(
         &self,
         request: Request<proto::PublishModule>,
@@ -72,6 +103,19 @@ impl proto::command_center_server::CommandCenter for Runtime {
 
         Ok(Response::new(resp))
     }
+
+    async fn current_version(
+        &self,
+        request: Request<proto::CurrentVersionRequest>,
+    ) -> Result<Response<proto::CurrentVersionResponse>, Status> {
+        let proto::CurrentVersionRequest { name, id } = request.into_inner();
+        let name = Category::new(name).map_err(|_| Status::invalid_argument("invalid name"))?;
+        let id = ID::new(id).map_err(|_| Status::invalid_argument("invalid id"))?;
+
+        let version = self.current_version(name, id).map_err(error_status)?;
+
+        Ok(Response::new(proto::CurrentVersionResponse { version }))
+    }
 }
 
 #[tonic::async_trait]
@@ -121,3 +165,55 @@ impl proto::projection_server::Projection for Runtime {
         Ok(Response::new(resp))
     }
 }
+
+async fn execute_command(
+    runtime: &Runtime,
+    command: proto::ExecuteCommand,
+) -> Result<proto::ExecuteResponse, Status> {
+    let proto::ExecuteCommand {
+        name,
+        id,
+        command,
+        payload,
+    } = command;
+    let name = Category::new(name).map_err(|_| Status::invalid_argument("invalid name"))?;
+    let id = ID::new(id).map_err(|_| Status::invalid_argument("invalid id"))?;
+    let payload = serde_json::from_str(&payload)
+        .map_err(|err| Status::invalid_argument(format!("invalid payload: {err}")))?;
+
+    match runtime.execute(name, id, command, payload).await {
+        Ok(Ok(events)) => Ok(proto::ExecuteResponse {
+            success: true,
+            events: events
+                .into_iter()
+                .map(proto::Message::try_from)
+                .collect::<Result<_, _>>()
+                .map_err(|err| Status::internal(err.to_string()))?,
+            message: "ok".to_string(),
+        }),
+        Ok(Err(err)) => Ok(proto::ExecuteResponse {
+            success: false,
+            events: vec![],
+            message: serde_json::to_string(&err)
+                .map_err(|err| Status::internal(format!("failed to serialize error: {err}")))?,
+        }),
+        Err(err) => Err(error_status(err)),
+    }
+}
+
+/// Maps an error to a status, using the message store's error code if
+/// available.
+fn error_status(err: anyhow::Error) -> Status {
+    let Some(code) = err.downcast_ref::<Error>().map(Error::code) else {
+        return Status::internal(err.to_string());
+    };
+
+    match code {
+        ErrorCode::Conflict => Status::already_exists(err.to_string()),
+        ErrorCode::Concurrency => Status::aborted(err.to_string()),
+        ErrorCode::NotFound => Status::not_found(err.to_string()),
+        ErrorCode::Unavailable => Status::unavailable(err.to_string()),
+        ErrorCode::Invalid => Status::invalid_argument(err.to_string()),
+        ErrorCode::Internal => Status::internal(err.to_string()),
+    }
+}
@@ -1,19 +1,24 @@
 use std::path::PathBuf;
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
 
 use anyhow::Result;
 use serde_json::Value;
-use thalo::stream_name::{Category, ID};
+use thalo::stream_name::{Category, StreamName, ID};
 use thalo_message_store::message::Message;
 use thalo_message_store::MessageStore;
 use tokio::fs;
 use tokio::sync::{broadcast, mpsc};
-use tracing::instrument;
+use tracing::field::{debug, Empty};
+use tracing::{instrument, Span};
 use wasmtime::Engine;
 
 use crate::broadcaster::BroadcasterHandle;
 use crate::command::CommandGatewayHandle;
-use crate::projection::{EventInterest, ProjectionGatewayHandle};
+use crate::module::{CommandTimeout, EpochTicker, Event};
+use crate::projection::{EventInterest, ProjectionGatewayHandle, ProjectionProgress};
 use crate::relay::Relay;
+use crate::upcaster::{Upcaster, Upcasters};
 
 #[derive(Clone)]
 pub struct Runtime {
@@ -22,6 +27,10 @@ pub struct Runtime {
     event_tx: broadcast::Sender<Message<'static>>,
     command_gateway: CommandGatewayHandle,
     projection_gateway: ProjectionGatewayHandle,
+    upcasters: Upcasters,
+    engine: Engine,
+    command_timeout: CommandTimeout,
+    epoch_ticker: Arc<OnceLock<EpochTicker>>,
 }
 
 impl Runtime {
@@ -32,7 +41,13 @@ impl Runtime {
         cache_size: u64,
     ) -> Result<Self> {
         let mut config = wasmtime::Config::new();
-        config.async_support(true).wasm_component_model(true);
+        // Epoch interruption is enabled up front, as a command timeout may be
+        // set after modules have been compiled. The epoch only advances once a
+        // timeout is set.
+        config
+            .async_support(true)
+            .wasm_component_model(true)
+            .epoch_interruption(true);
         let engine = Engine::new(&config)?;
 
         let (event_tx, subscriber) = broadcast::channel(1024);
@@ -44,12 +59,16 @@ impl Runtime {
         let projection_gateway = ProjectionGatewayHandle::new(message_store.clone(), subscriber);
 
         let modules_path = modules_path.into();
+        let upcasters = Upcasters::default();
+        let command_timeout = CommandTimeout::default();
         let command_gateway = CommandGatewayHandle::new(
-            engine,
+            engine.clone(),
             message_store.clone(),
             relay.clone(),
             broadcaster.clone(),
             cache_size,
+            upcasters.clone(),
+            command_timeout.clone(),
             modules_path.clone(),
         );
 
@@ -59,14 +78,52 @@ impl Runtime {
             event_tx,
             command_gateway,
             projection_gateway,
+            upcasters,
+            engine,
+            command_timeout,
+            epoch_ticker: Arc::default(),
         })
     }
 
+    /// Sets the maximum duration a module may run for when handling a command
+    /// or applying events.
+    ///
+    /// Calls exceeding the timeout are aborted with a "command timed out"
+    /// error, and the aggregate is restarted. This protects the runtime from
+    /// modules which loop forever, and applies to modules which are already
+    /// running. The timeout is enforced with a precision of [`EPOCH_TICK`].
+    ///
+    /// By default, there is no timeout. Setting one starts a thread which
+    /// advances the engine's epoch, and stops once the runtime and all of its
+    /// clones are dropped.
+    ///
+    /// [`EPOCH_TICK`]: crate::module::EPOCH_TICK
+    pub fn with_command_timeout(self, timeout: Duration) -> Self {
+        self.epoch_ticker
+            .get_or_init(|| EpochTicker::start(self.engine.clone()));
+        self.command_timeout.set(Some(timeout));
+        self
+    }
+
     pub fn message_store(&self) -> &MessageStore {
         &self.message_store
     }
 
-    #[instrument(skip(self, payload))]
+    /// Registers an upcaster for an aggregate's events.
+    ///
+    /// Upcasters run in the order they were registered, each time an event is
+    /// replayed to rebuild an aggregate. Aggregates which are already cached
+    /// are not rebuilt, so upcasters should be registered before any commands
+    /// are executed.
+    pub fn register_upcaster(&self, name: Category<'static>, upcaster: impl Upcaster + 'static) {
+        self.upcasters.register(name, Box::new(upcaster));
+    }
+
+    /// Executes a command, persisting the resulting events.
+    ///
+    /// The current span records the emitted event types and the resulting
+    /// stream version, so a single span captures the outcome of the command.
+    #[instrument(skip(self, payload), fields(events = Empty, version = Empty))]
     pub async fn execute(
         &self,
         name: Category<'static>,
@@ -74,11 +131,66 @@ impl Runtime {
         command: String,
         payload: Value,
     ) -> Result<Result<Vec<Message<'static>>, serde_json::Value>> {
-        self.command_gateway
+        let res = self
+            .command_gateway
             .execute(name, id, command, payload)
+            .await;
+        if let Ok(Ok(messages)) = &res {
+            let span = Span::current();
+            let events: Vec<_> = messages.iter().map(|msg| msg.msg_type.as_ref()).collect();
+            span.record("events", debug(&events));
+            if let Some(message) = messages.last() {
+                span.record("version", message.position);
+            }
+        }
+
+        res
+    }
+
+    /// Handles a command without persisting the resulting events.
+    ///
+    /// The aggregate is rebuilt as usual and the command is handled, but the
+    /// returned events are neither applied nor written to the message store.
+    /// This is useful for validating a command before executing it, though the
+    /// result may be stale if other commands are executed in the meantime.
+    #[instrument(skip(self, payload))]
+    pub async fn dry_run(
+        &self,
+        name: Category<'static>,
+        id: ID<'static>,
+        command: String,
+        payload: Value,
+    ) -> Result<Result<Vec<Event<'static>>, serde_json::Value>> {
+        self.command_gateway
+            .dry_run(name, id, command, payload)
             .await
     }
 
+    /// Returns the current version of an aggregate's stream, without replaying
+    /// its events.
+    ///
+    /// Only the last event in the stream is read, so this doesn't depend on
+    /// the length of the stream.
+    ///
+    /// The version is the position of the last event in the stream, and can be
+    /// used by clients for their own optimistic concurrency checks.
+    /// Returns `None` if the stream has no events.
+    pub fn current_version(&self, name: Category<'static>, id: ID<'static>) -> Result<Option<u64>> {
+        let stream_name = StreamName::from_parts(name, Some(&id))?;
+        let mut stream = self.message_store.stream(stream_name)?;
+        Ok(stream.version()?)
+    }
+
+    /// Deletes an aggregate's stream, returning the number of events deleted.
+    ///
+    /// The aggregate's cached state is evicted, so the next command rebuilds it
+    /// from an empty stream and its events start again from position `0`.
+    /// Deleted events are skipped by projections which haven't processed them
+    /// yet.
+    pub async fn delete_stream(&self, name: Category<'static>, id: ID<'static>) -> Result<usize> {
+        self.command_gateway.delete_stream(name, id).await
+    }
+
     pub async fn save_module(
         &self,
         name: Category<'static>,
@@ -92,6 +204,19 @@ impl Runtime {
             .await
     }
 
+    /// Reloads an aggregate's module from the modules directory, without
+    /// restarting the runtime.
+    ///
+    /// New commands are handled by the reloaded module, rebuilding each
+    /// aggregate from its events, while commands already queued for the
+    /// previous module finish on it.
+    pub async fn reload_module(&self, name: Category<'static>) -> Result<()> {
+        let path = self.modules_path.join(format!("{name}.wasm"));
+        self.command_gateway
+            .start_module_from_file(name, path)
+            .await
+    }
+
     pub async fn start_projection(
         &self,
         tx: mpsc::Sender<Message<'static>>,
@@ -103,6 +228,47 @@ impl Runtime {
             .await
     }
 
+    /// Rebuilds a projection by resetting its position to the beginning of the
+    /// global event log.
+    ///
+    /// If the projection is running, its subscription is closed. When it
+    /// subscribes again, every historical event is replayed before live events
+    /// resume. Acknowledgements received in between are ignored, as they're
+    /// for events sent before the reset. Projections should clear their read
+    /// model, including the position returned by
+    /// [`Projection::last_global_id`], before resubscribing, otherwise replayed
+    /// events are skipped.
+    ///
+    /// The rebuild's progress can be followed with
+    /// [`Runtime::projection_progress`].
+    ///
+    /// [`Projection::last_global_id`]: crate::Projection::last_global_id
+    pub async fn rebuild_projection(&self, name: impl Into<String>) -> Result<()> {
+        self.projection_gateway.reset_projection(name.into()).await
+    }
+
+    /// Returns how far a projection has progressed through the global event
+    /// log.
+    pub async fn projection_progress(&self, name: impl Into<String>) -> Result<ProjectionProgress> {
+        self.projection_gateway.progress(name.into()).await
+    }
+
+    /// Moves a projection to a global id, as if every event up to and including
+    /// `global_id` had been acknowledged.
+    ///
+    /// As with [`Runtime::rebuild_projection`], a running projection's
+    /// subscription is closed, and it resumes from the new position when it
+    /// subscribes again.
+    pub async fn set_projection_position(
+        &self,
+        name: impl Into<String>,
+        global_id: u64,
+    ) -> Result<()> {
+        self.projection_gateway
+            .set_position(name.into(), global_id)
+            .await
+    }
+
     pub fn subscribe_events(&self) -> broadcast::Receiver<Message<'static>> {
         self.event_tx.subscribe()
     }
@@ -0,0 +1,64 @@
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+use serde_json::Value;
+use thalo::stream_name::Category;
+
+/// Transforms persisted events into their current schema before they are
+/// applied to an aggregate.
+///
+/// Upcasters allow event schemas to evolve, such as renaming fields or adding
+/// defaults, without rewriting historical events in the message store.
+/// Events are only upcast when rebuilding aggregates, and are stored unchanged.
+pub trait Upcaster: Send + Sync {
+    /// Upcasts an event, returning its new event type and data.
+    ///
+    /// Events which don't need upcasting should be returned unchanged.
+    fn upcast(&self, event_type: String, data: Value) -> (String, Value);
+}
+
+impl<F> Upcaster for F
+where
+    F: Fn(String, Value) -> (String, Value) + Send + Sync,
+{
+    fn upcast(&self, event_type: String, data: Value) -> (String, Value) {
+        self(event_type, data)
+    }
+}
+
+/// Upcasters registered per aggregate category.
+#[derive(Clone, Default)]
+pub(crate) struct Upcasters(Arc<RwLock<HashMap<String, UpcasterChain>>>);
+
+type UpcasterChain = Vec<Box<dyn Upcaster>>;
+
+impl Upcasters {
+    pub(crate) fn register(&self, category: Category<'static>, upcaster: Box<dyn Upcaster>) {
+        self.0
+            .write()
+            .unwrap()
+            .entry(category.into_string())
+            .or_default()
+            .push(upcaster);
+    }
+
+    /// Runs an event through each upcaster for the category, in the order they
+    /// were registered.
+    pub(crate) fn upcast(
+        &self,
+        category: &str,
+        event_type: String,
+        data: Value,
+    ) -> (String, Value) {
+        let upcasters = self.0.read().unwrap();
+        let Some(upcasters) = upcasters.get(category) else {
+            return (event_type, data);
+        };
+
+        upcasters
+            .iter()
+            .fold((event_type, data), |(event_type, data), upcaster| {
+                upcaster.upcast(event_type, data)
+            })
+    }
+}
@@ -0,0 +1,74 @@
+;; A minimal aggregate which handles every command with a single "HandledByV1"
+;; event, and ignores applied events.
+(component
+  (core module $m
+    (import "entity" "new" (func $new (param i32) (result i32)))
+    (memory (export "memory") 1)
+    (global $heap (mut i32) (i32.const 1024))
+    (func (export "realloc") (param i32 i32 i32 i32) (result i32)
+      (local $ptr i32)
+      (local.set $ptr
+        (i32.and
+          (i32.add (global.get $heap) (i32.sub (local.get 2) (i32.const 1)))
+          (i32.sub (i32.const 0) (local.get 2))))
+      (global.set $heap (i32.add (local.get $ptr) (local.get 3)))
+      (local.get $ptr))
+    (data (i32.const 16) "HandledByV1")
+    (data (i32.const 32) "{}")
+    (data (i32.const 64) "\10\00\00\00\0b\00\00\00\20\00\00\00\02\00\00\00")
+    (data (i32.const 96) "\00\00\00\00\40\00\00\00\01\00\00\00")
+    (func (export "constructor") (param i32 i32) (result i32)
+      (call $new (i32.const 0)))
+    (func (export "apply") (param i32 i32 i32) (result i32)
+      (i32.const 112))
+    (func (export "handle") (param i32 i32 i32 i32 i32) (result i32)
+      (i32.const 96))
+  )
+  (type $entity (resource (rep i32)))
+  (core func $entity_new (canon resource.new $entity))
+  (core instance $entity_instance (export "new" (func $entity_new)))
+  (core instance $i (instantiate $m (with "entity" (instance $entity_instance))))
+
+  (type $event (record (field "event" string) (field "payload" string)))
+  (type $command (record (field "command" string) (field "payload" string)))
+  (type $error (variant
+    (case "command" (tuple string string))
+    (case "deserialize-command" (tuple string string))
+    (case "deserialize-context" string)
+    (case "deserialize-event" (tuple string string))
+    (case "serialize-error" (tuple string string))
+    (case "serialize-event" string)))
+
+  (func $constructor (param "id" string) (result (own $entity))
+    (canon lift (core func $i "constructor") (memory $i "memory") (realloc (func $i "realloc"))))
+  (func $apply (param "self" (borrow $entity)) (param "events" (list $event)) (result (result (error $error)))
+    (canon lift (core func $i "apply") (memory $i "memory") (realloc (func $i "realloc"))))
+  (func $handle (param "self" (borrow $entity)) (param "command" $command) (result (result (list $event) (error $error)))
+    (canon lift (core func $i "handle") (memory $i "memory") (realloc (func $i "realloc"))))
+
+  (component $aggregate
+    (import "import-type-event" (type $event' (eq $event)))
+    (import "import-type-command" (type $command' (eq $command)))
+    (import "import-type-error" (type $error' (eq $error)))
+    (import "import-type-entity" (type $entity' (sub resource)))
+    (import "import-constructor-entity" (func $constructor' (param "id" string) (result (own $entity'))))
+    (import "import-method-entity-apply" (func $apply' (param "self" (borrow $entity')) (param "events" (list $event')) (result (result (error $error')))))
+    (import "import-method-entity-handle" (func $handle' (param "self" (borrow $entity')) (param "command" $command') (result (result (list $event') (error $error')))))
+    (export $event'' "event" (type $event'))
+    (export $command'' "command" (type $command'))
+    (export $error'' "error" (type $error'))
+    (export $entity'' "entity" (type $entity'))
+    (export "[constructor]entity" (func $constructor') (func (param "id" string) (result (own $entity''))))
+    (export "[method]entity.apply" (func $apply') (func (param "self" (borrow $entity'')) (param "events" (list $event'')) (result (result (error $error'')))))
+    (export "[method]entity.handle" (func $handle') (func (param "self" (borrow $entity'')) (param "command" $command'') (result (result (list $event'') (error $error'')))))
+  )
+  (instance $aggregate_instance (instantiate $aggregate
+    (with "import-type-event" (type $event))
+    (with "import-type-command" (type $command))
+    (with "import-type-error" (type $error))
+    (with "import-type-entity" (type $entity))
+    (with "import-constructor-entity" (func $constructor))
+    (with "import-method-entity-apply" (func $apply))
+    (with "import-method-entity-handle" (func $handle))))
+  (export "aggregate" (instance $aggregate_instance))
+)
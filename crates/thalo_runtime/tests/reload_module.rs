@@ -0,0 +1,57 @@
+use std::fs;
+use std::time::Duration;
+
+use serde_json::json;
+use thalo::stream_name::{Category, ID};
+use thalo_message_store::MessageStore;
+use thalo_runtime::relay::Relay;
+use thalo_runtime::Runtime;
+
+const AGGREGATE_V1: &str = include_str!("aggregate.wat");
+
+async fn execute(runtime: &Runtime, id: &str) -> String {
+    let messages = runtime
+        .execute(
+            Category::new("counter").unwrap(),
+            ID::new(id.to_string()).unwrap(),
+            "Increment".to_string(),
+            json!({ "amount": 1 }),
+        )
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(messages.len(), 1);
+    messages[0].msg_type.to_string()
+}
+
+#[tokio::test]
+async fn reloaded_module_handles_new_commands() {
+    let dir = std::env::temp_dir().join(format!("thalo-reload-module-{}", std::process::id()));
+    let modules_path = dir.join("modules");
+    fs::create_dir_all(&modules_path).unwrap();
+
+    let message_store = MessageStore::open(dir.join("db")).unwrap();
+    let runtime = Runtime::new(message_store, Relay::Noop, &modules_path, 100)
+        .await
+        .unwrap();
+    let name = Category::new("counter").unwrap();
+
+    runtime
+        .save_module(name.clone(), AGGREGATE_V1)
+        .await
+        .unwrap();
+    assert_eq!(execute(&runtime, "a").await, "HandledByV1");
+
+    let aggregate_v2 = AGGREGATE_V1.replace("HandledByV1", "HandledByV2");
+    fs::write(modules_path.join("counter.wasm"), aggregate_v2).unwrap();
+    runtime.reload_module(name).await.unwrap();
+
+    // The previous module's handler stops in the background once it's replaced,
+    // and must not restart itself over the reloaded module.
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    assert_eq!(execute(&runtime, "a").await, "HandledByV2");
+    assert_eq!(execute(&runtime, "b").await, "HandledByV2");
+
+    fs::remove_dir_all(dir).unwrap();
+}